@@ -0,0 +1,226 @@
+// ambient.rs
+#![allow(dead_code)]
+
+use raylib::prelude::*;
+use std::f32::consts::PI;
+use crate::Uniforms;
+use crate::framebuffer::Framebuffer;
+use crate::shaders::{hash, smoothstep};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AmbientMode {
+    // el mismo color en todas direcciones
+    Constant,
+    // cielo arriba y suelo abajo, mezclados según la normal
+    Hemisphere,
+    // irradiancia del fondo estrellado proyectada en armónicos esféricos
+    SphericalHarmonics,
+}
+
+/// Fondo procedural de la escena: el color de fondo de siempre, una banda galáctica tenue
+/// y estrellas. Es también la fuente de la luz ambiente en modo `SphericalHarmonics`.
+#[derive(Clone, Copy, Debug)]
+pub struct Environment {
+    pub base_color: Vector3,
+    pub band_color: Vector3,
+    pub band_normal: Vector3,   // normal del plano de la banda galáctica
+    pub band_width: f32,
+    pub star_density: f32,      // fracción de celdas con estrella, en [0, 1]
+    pub star_brightness: f32,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Environment {
+            base_color: Vector3::new(35.0 / 255.0, 6.0 / 255.0, 48.0 / 255.0),
+            band_color: Vector3::new(0.35, 0.25, 0.45),
+            band_normal: Vector3::new(0.3, 1.0, 0.2).normalized(),
+            band_width: 0.25,
+            star_density: 0.004,
+            star_brightness: 0.9,
+        }
+    }
+}
+
+impl Environment {
+    /// Radiancia del fondo en la dirección `dir` (espacio mundo).
+    pub fn radiance(&self, dir: Vector3) -> Vector3 {
+        let dir = dir.normalized();
+
+        let height = dir.dot(self.band_normal) / self.band_width.max(1e-3);
+        let band = (-height * height).exp();
+
+        // una estrella como mucho por celda de una grilla sobre la dirección
+        let cell = dir * 120.0;
+        let cell = Vector3::new(cell.x.floor(), cell.y.floor(), cell.z.floor());
+        let id = cell.x * 12.9898 + cell.y * 78.233 + cell.z * 37.719;
+        let star = if hash(id) < self.star_density * (1.0 + 2.0 * band) {
+            self.star_brightness * (0.3 + 0.7 * hash(id + 1.0))
+        } else {
+            0.0
+        };
+
+        self.base_color + self.band_color * (band * 0.5) + Vector3::new(star, star, star)
+    }
+}
+
+/// Irradiancia en armónicos esféricos de orden 2 (9 coeficientes RGB), según
+/// Ramamoorthi y Hanrahan, "An Efficient Representation for Irradiance Environment Maps".
+#[derive(Clone, Copy, Debug)]
+pub struct ShIrradiance {
+    coefficients: [Vector3; 9],
+}
+
+fn sh_basis(d: Vector3) -> [f32; 9] {
+    [
+        0.282095,
+        0.488603 * d.y,
+        0.488603 * d.z,
+        0.488603 * d.x,
+        1.092548 * d.x * d.y,
+        1.092548 * d.y * d.z,
+        0.315392 * (3.0 * d.z * d.z - 1.0),
+        1.092548 * d.x * d.z,
+        0.546274 * (d.x * d.x - d.y * d.y),
+    ]
+}
+
+impl ShIrradiance {
+    /// Proyecta el entorno sobre la base muestreando `samples` direcciones repartidas
+    /// uniformemente (espiral de Fibonacci). Se hace una vez al iniciar.
+    pub fn from_environment(environment: &Environment, samples: usize) -> Self {
+        let samples = samples.max(1);
+        let mut coefficients = [Vector3::zero(); 9];
+        let golden_angle = PI * (3.0 - 5.0_f32.sqrt());
+
+        for i in 0..samples {
+            let y = 1.0 - 2.0 * (i as f32 + 0.5) / samples as f32;
+            let r = (1.0 - y * y).sqrt();
+            let phi = golden_angle * i as f32;
+            let dir = Vector3::new(r * phi.cos(), y, r * phi.sin());
+
+            let radiance = environment.radiance(dir);
+            for (coefficient, basis) in coefficients.iter_mut().zip(sh_basis(dir)) {
+                *coefficient += radiance * basis;
+            }
+        }
+
+        // cada muestra representa 4π/N del ángulo sólido
+        let weight = 4.0 * PI / samples as f32;
+        for coefficient in &mut coefficients {
+            *coefficient = *coefficient * weight;
+        }
+
+        ShIrradiance { coefficients }
+    }
+
+    /// Radiancia reflejada por una superficie blanca mate con normal `n` (irradiancia / π).
+    pub fn evaluate(&self, n: Vector3) -> Vector3 {
+        let [l00, l1m1, l10, l11, l2m2, l2m1, l20, l21, l22] = self.coefficients;
+        let (c1, c2, c3, c4, c5) = (0.429043, 0.511664, 0.743125, 0.886227, 0.247708);
+
+        let irradiance = l22 * (c1 * (n.x * n.x - n.y * n.y))
+            + l20 * (c3 * n.z * n.z)
+            + l00 * c4
+            - l20 * c5
+            + (l2m2 * (n.x * n.y) + l21 * (n.x * n.z) + l2m1 * (n.y * n.z)) * (2.0 * c1)
+            + (l11 * n.x + l1m1 * n.y + l10 * n.z) * (2.0 * c2);
+
+        let irradiance = irradiance / PI;
+        Vector3::new(irradiance.x.max(0.0), irradiance.y.max(0.0), irradiance.z.max(0.0))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct AmbientParams {
+    pub mode: AmbientMode,
+    pub intensity: f32,
+    pub constant_color: Vector3,
+    pub sky_color: Vector3,
+    pub ground_color: Vector3,
+    pub up: Vector3,
+    pub environment: ShIrradiance,
+}
+
+impl AmbientParams {
+    pub fn new(environment: &Environment) -> Self {
+        AmbientParams {
+            mode: AmbientMode::SphericalHarmonics,
+            intensity: 1.0,
+            constant_color: Vector3::new(0.08, 0.07, 0.1),
+            sky_color: Vector3::new(0.22, 0.2, 0.32),
+            ground_color: Vector3::new(0.04, 0.02, 0.05),
+            up: Vector3::new(0.0, 1.0, 0.0),
+            environment: ShIrradiance::from_environment(environment, 4096),
+        }
+    }
+
+    /// Siguiente modo, para alternarlos con una tecla.
+    pub fn next_mode(&mut self) {
+        self.mode = match self.mode {
+            AmbientMode::Constant => AmbientMode::Hemisphere,
+            AmbientMode::Hemisphere => AmbientMode::SphericalHarmonics,
+            AmbientMode::SphericalHarmonics => AmbientMode::Constant,
+        };
+    }
+}
+
+/// Luz ambiente que llega a una superficie con normal `normal` (espacio mundo). Los shaders
+/// la multiplican por su albedo (y por Ka si tienen material) y por la oclusión del SSAO.
+pub fn ambient_light(normal: Vector3, uniforms: &Uniforms) -> Vector3 {
+    let ambient = &uniforms.ambient;
+    let light = match ambient.mode {
+        AmbientMode::Constant => ambient.constant_color,
+        AmbientMode::Hemisphere => {
+            let t = smoothstep(-1.0, 1.0, normal.dot(ambient.up));
+            ambient.ground_color.lerp(ambient.sky_color, t)
+        }
+        AmbientMode::SphericalHarmonics => ambient.environment.evaluate(normal),
+    };
+    light * ambient.intensity
+}
+
+/// Dibuja el entorno en los píxeles de fondo (sin geometría), con la dirección de la
+/// cámara que pasa por cada píxel.
+pub fn draw_environment(framebuffer: &mut Framebuffer, environment: &Environment, uniforms: &Uniforms) {
+    let projection = &uniforms.projection_matrix;
+    let viewport = &uniforms.viewport_matrix;
+    let view = &uniforms.view_matrix;
+    // ejes de la cámara en espacio mundo (filas de la parte de rotación de la vista)
+    let right = Vector3::new(view.m0, view.m4, view.m8);
+    let up = Vector3::new(view.m1, view.m5, view.m9);
+    let back = Vector3::new(view.m2, view.m6, view.m10);
+    let orthographic = projection.m11 == 0.0;
+
+    let area = framebuffer.scissor();
+    for y in area.y..area.y + area.height {
+        for x in area.x..area.x + area.width {
+            if framebuffer.get_depth(x, y).is_finite() {
+                continue;
+            }
+
+            // en perspectiva, el rayo pasa por el punto del píxel en el plano z = -1 de la vista
+            let dir_view = if orthographic {
+                Vector3::new(0.0, 0.0, -1.0)
+            } else {
+                let ndc_x = (x as f32 + 0.5 - viewport.m12) / viewport.m0;
+                let ndc_y = (y as f32 + 0.5 - viewport.m13) / viewport.m5;
+                Vector3::new(
+                    (ndc_x + projection.m8 - projection.m12) / projection.m0,
+                    (ndc_y + projection.m9 - projection.m13) / projection.m5,
+                    -1.0,
+                )
+            };
+            let dir = right * dir_view.x + up * dir_view.y + back * dir_view.z;
+
+            let color = environment.radiance(dir);
+            framebuffer.set_current_color(Color::new(
+                (color.x.clamp(0.0, 1.0) * 255.0) as u8,
+                (color.y.clamp(0.0, 1.0) * 255.0) as u8,
+                (color.z.clamp(0.0, 1.0) * 255.0) as u8,
+                255,
+            ));
+            framebuffer.set_pixel(x, y);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+// bounds.rs
+#![allow(dead_code)]
+
+use raylib::prelude::*;
+use crate::matrix::multiply_matrix_vector4;
+use crate::vertex::Vertex;
+
+/// Volúmenes que envuelven una malla: una esfera (prueba rápida) y una caja alineada a los
+/// ejes (más ajustada). En espacio objeto al cargar, en espacio mundo con `transformed`.
+#[derive(Clone, Copy, Debug)]
+pub struct BoundingVolume {
+    pub center: Vector3,
+    pub radius: f32,
+    pub min: Vector3,
+    pub max: Vector3,
+}
+
+impl BoundingVolume {
+    pub fn from_vertices(vertices: &[Vertex]) -> Self {
+        if vertices.is_empty() {
+            return BoundingVolume { center: Vector3::zero(), radius: 0.0, min: Vector3::zero(), max: Vector3::zero() };
+        }
+
+        let mut min = Vector3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+        let mut max = Vector3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for vertex in vertices {
+            let p = vertex.position;
+            min = Vector3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+            max = Vector3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+        }
+
+        // la esfera se centra en la caja; el radio es el del vértice más lejano
+        let center = (min + max) * 0.5;
+        let radius = vertices.iter().map(|v| (v.position - center).length()).fold(0.0, f32::max);
+        BoundingVolume { center, radius, min, max }
+    }
+
+    /// Los mismos volúmenes llevados a espacio mundo con `model_matrix`. La caja se recalcula
+    /// para que siga alineada a los ejes (método de Arvo).
+    pub fn transformed(&self, model_matrix: &Matrix) -> Self {
+        let m = model_matrix;
+        let c = multiply_matrix_vector4(m, &Vector4::new(self.center.x, self.center.y, self.center.z, 1.0));
+        let center = Vector3::new(c.x, c.y, c.z);
+
+        let scale = Vector3::new(m.m0, m.m1, m.m2).length()
+            .max(Vector3::new(m.m4, m.m5, m.m6).length())
+            .max(Vector3::new(m.m8, m.m9, m.m10).length());
+
+        let rows = [[m.m0, m.m4, m.m8], [m.m1, m.m5, m.m9], [m.m2, m.m6, m.m10]];
+        let translation = [m.m12, m.m13, m.m14];
+        let (old_min, old_max) = ([self.min.x, self.min.y, self.min.z], [self.max.x, self.max.y, self.max.z]);
+        let mut min = translation;
+        let mut max = translation;
+        for i in 0..3 {
+            for j in 0..3 {
+                let a = rows[i][j] * old_min[j];
+                let b = rows[i][j] * old_max[j];
+                min[i] += a.min(b);
+                max[i] += a.max(b);
+            }
+        }
+
+        BoundingVolume {
+            center,
+            radius: self.radius * scale,
+            min: Vector3::new(min[0], min[1], min[2]),
+            max: Vector3::new(max[0], max[1], max[2]),
+        }
+    }
+}
+
+/// Los 6 planos de la pirámide de visión (izquierda, derecha, abajo, arriba, cerca, lejos),
+/// con la normal hacia adentro: un punto está dentro si `n·p + d >= 0` para todos.
+#[derive(Clone, Copy, Debug)]
+pub struct Frustum {
+    pub planes: [Vector4; 6],
+}
+
+impl Frustum {
+    /// Extrae los planos de una matriz view-projection (Gribb y Hartmann).
+    pub fn from_matrix(view_projection: &Matrix) -> Self {
+        let m = view_projection;
+        let row = |i: usize| match i {
+            0 => Vector4::new(m.m0, m.m4, m.m8, m.m12),
+            1 => Vector4::new(m.m1, m.m5, m.m9, m.m13),
+            2 => Vector4::new(m.m2, m.m6, m.m10, m.m14),
+            _ => Vector4::new(m.m3, m.m7, m.m11, m.m15),
+        };
+        let (r0, r1, r2, r3) = (row(0), row(1), row(2), row(3));
+        let add = |a: Vector4, b: Vector4| Vector4::new(a.x + b.x, a.y + b.y, a.z + b.z, a.w + b.w);
+        let sub = |a: Vector4, b: Vector4| Vector4::new(a.x - b.x, a.y - b.y, a.z - b.z, a.w - b.w);
+        let normalize = |p: Vector4| {
+            let length = Vector3::new(p.x, p.y, p.z).length().max(1e-8);
+            Vector4::new(p.x / length, p.y / length, p.z / length, p.w / length)
+        };
+
+        Frustum {
+            planes: [
+                normalize(add(r3, r0)),
+                normalize(sub(r3, r0)),
+                normalize(add(r3, r1)),
+                normalize(sub(r3, r1)),
+                normalize(add(r3, r2)),
+                normalize(sub(r3, r2)),
+            ],
+        }
+    }
+
+    fn distance(plane: &Vector4, point: Vector3) -> f32 {
+        plane.x * point.x + plane.y * point.y + plane.z * point.z + plane.w
+    }
+
+    pub fn intersects_sphere(&self, center: Vector3, radius: f32) -> bool {
+        self.planes.iter().all(|plane| Frustum::distance(plane, center) >= -radius)
+    }
+
+    /// Prueba con el vértice de la caja más adentro de cada plano.
+    pub fn intersects_aabb(&self, min: Vector3, max: Vector3) -> bool {
+        self.planes.iter().all(|plane| {
+            let corner = Vector3::new(
+                if plane.x >= 0.0 { max.x } else { min.x },
+                if plane.y >= 0.0 { max.y } else { min.y },
+                if plane.z >= 0.0 { max.z } else { min.z },
+            );
+            Frustum::distance(plane, corner) >= 0.0
+        })
+    }
+
+    /// Un volumen en espacio mundo está (al menos en parte) dentro: primero la esfera, que
+    /// es barata, y si pasa, la caja.
+    pub fn contains(&self, volume: &BoundingVolume) -> bool {
+        self.intersects_sphere(volume.center, volume.radius) && self.intersects_aabb(volume.min, volume.max)
+    }
+}
@@ -2,10 +2,195 @@
 #![allow(dead_code)]
 
 use raylib::prelude::*;
-use crate::matrix::create_view_matrix;
+use crate::bounds::Frustum;
+use crate::input::{Action, Input};
+use crate::matrix::{create_orthographic_matrix, create_projection_matrix, create_view_matrix, multiply_matrices, new_matrix4};
 use std::f32::consts::PI;
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CameraMode {
+    // gira alrededor de `target` con yaw/pitch/distance
+    Orbit,
+    // vuelo libre de 6 grados de libertad con el ratón capturado
+    FreeFly,
+    // sigue a un objeto en movimiento desde atrás, con un brazo de resorte
+    Chase,
+}
+
+/// Parámetros de la cámara de persecución.
+#[derive(Clone, Copy, Debug)]
+pub struct ChaseParams {
+    // posición de la cámara respecto del objeto, en su espacio local (x derecha, y arriba,
+    // z atrás), así que escala con el tamaño del objeto
+    pub offset: Vector3,
+    pub position_stiffness: f32,  // frecuencia del resorte de la posición (1/s)
+    pub look_stiffness: f32,      // frecuencia del resorte del punto al que se mira (1/s)
+    pub look_ahead: f32,          // segundos de adelanto del punto mirado según la velocidad
+    pub lock_roll: bool,          // el "arriba" de la cámara sigue al del objeto
+}
+
+impl Default for ChaseParams {
+    fn default() -> Self {
+        ChaseParams {
+            offset: Vector3::new(0.0, 0.8, 3.0),
+            position_stiffness: 4.0,
+            look_stiffness: 8.0,
+            look_ahead: 0.3,
+            lock_roll: false,
+        }
+    }
+}
+
+// Estado de los resortes entre cuadros
+#[derive(Clone, Copy, Debug, Default)]
+struct ChaseState {
+    last_subject: Option<Vector3>,
+    eye_velocity: Vector3,
+    look_velocity: Vector3,
+    up_velocity: Vector3,
+}
+
+/// Resorte críticamente amortiguado (sin rebote): acerca `current` a `target` con
+/// frecuencia `omega`, guardando la velocidad entre cuadros. Aproximación de la
+/// exponencial de "Game Programming Gems 4", estable con cualquier `dt`.
+fn smooth_damp(current: Vector3, target: Vector3, velocity: &mut Vector3, omega: f32, dt: f32) -> Vector3 {
+    let x = omega * dt;
+    let decay = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+    let change = current - target;
+    let temp = (*velocity + change * omega) * dt;
+    *velocity = (*velocity - temp * omega) * decay;
+    target + (change + temp) * decay
+}
+
+// Rotación que lleva el -Z de la cámara a `forward` y el +Y a `up`
+fn look_rotation(forward: Vector3, up: Vector3) -> Quaternion {
+    let forward = forward.normalized();
+    let right = forward.cross(up).normalized();
+    let up = right.cross(forward);
+    Quaternion::from_matrix(new_matrix4(
+        right.x, up.x, -forward.x, 0.0,
+        right.y, up.y, -forward.y, 0.0,
+        right.z, up.z, -forward.z, 0.0,
+        0.0, 0.0, 0.0, 1.0,
+    ))
+    .normalized()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProjectionKind {
+    Perspective,
+    // sin perspectiva: para el mapa del sistema visto desde arriba y vistas técnicas de la nave
+    Orthographic,
+}
+
+/// Cómo proyecta la cámara. En ortográfica el alto visible sale de la distancia de la
+/// cámara y del FOV, así que el zoom de la órbita sigue funcionando y al alternar el objeto
+/// enfocado conserva su tamaño.
+#[derive(Clone, Copy, Debug)]
+pub struct Projection {
+    pub kind: ProjectionKind,
+    pub fov_y: f32,  // radianes
+    pub near: f32,
+    pub far: f32,
+}
+
+impl Default for Projection {
+    fn default() -> Self {
+        Projection {
+            kind: ProjectionKind::Perspective,
+            fov_y: PI / 3.0,
+            near: 0.1,
+            far: 100.0,
+        }
+    }
+}
+
+impl Projection {
+    pub fn matrix(&self, aspect: f32, distance: f32) -> Matrix {
+        match self.kind {
+            ProjectionKind::Perspective => create_projection_matrix(self.fov_y, aspect, self.near, self.far),
+            ProjectionKind::Orthographic => {
+                let half_height = distance * (self.fov_y / 2.0).tan();
+                let half_width = half_height * aspect;
+                create_orthographic_matrix(-half_width, half_width, -half_height, half_height, self.near, self.far)
+            }
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.kind = match self.kind {
+            ProjectionKind::Perspective => ProjectionKind::Orthographic,
+            ProjectionKind::Orthographic => ProjectionKind::Perspective,
+        };
+    }
+}
+
+/// Curvas para las transiciones de la cámara: reciben y devuelven un valor en [0, 1].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Easing {
+    Linear,
+    EaseInOutQuad,
+    EaseInOutCubic,
+    EaseOutExpo,
+}
+
+impl Easing {
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOutQuad => {
+                if t < 0.5 { 2.0 * t * t } else { 1.0 - (-2.0 * t + 2.0).powi(2) / 2.0 }
+            }
+            Easing::EaseInOutCubic => {
+                if t < 0.5 { 4.0 * t * t * t } else { 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0 }
+            }
+            Easing::EaseOutExpo => {
+                if t >= 1.0 { 1.0 } else { 1.0 - 2.0_f32.powf(-10.0 * t) }
+            }
+        }
+    }
+}
+
+/// Estado de la cámara de órbita: a qué mira, desde qué distancia y con qué ángulos.
+#[derive(Clone, Copy, Debug)]
+pub struct CameraPose {
+    pub target: Vector3,
+    pub distance: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+impl CameraPose {
+    // Orientación de la vista para estos ángulos (la cámara mira hacia el objetivo)
+    fn rotation(&self) -> Quaternion {
+        // mismo límite que la órbita: justo en los polos la vista no tiene "arriba"
+        let pitch = self.pitch.clamp(-PI / 2.0 + 0.01, PI / 2.0 - 0.01);
+        let to_eye = Vector3::new(
+            pitch.cos() * self.yaw.cos(),
+            pitch.sin(),
+            pitch.cos() * self.yaw.sin(),
+        );
+        look_rotation(-to_eye, Vector3::new(0.0, 1.0, 0.0))
+    }
+}
+
+// Una transición en curso entre dos poses
+struct CameraTransition {
+    from: CameraPose,
+    to: CameraPose,
+    from_rotation: Quaternion,
+    to_rotation: Quaternion,
+    elapsed: f32,
+    duration: f32,
+    easing: Easing,
+}
+
 pub struct Camera {
+    pub mode: CameraMode,
+    pub projection: Projection,
+    pub min_fov: f32,
+    pub max_fov: f32,
     pub eye: Vector3,
     pub target: Vector3,
     pub up: Vector3,
@@ -14,12 +199,41 @@ pub struct Camera {
     pub pitch: f32,
     pub distance: f32,
 
-    pub rotation_speed: f32,
-    pub zoom_speed: f32,
-    pub pan_speed: f32,
+    // velocidades del teclado, por segundo
+    pub rotation_speed: f32,  // radianes/s
+    pub zoom_speed: f32,      // unidades/s
+    pub pan_speed: f32,       // unidades/s
 
     pub min_distance: f32,
     pub max_distance: f32,
+
+    // ratón en la órbita: arrastre con el botón izquierdo gira, con el derecho o el medio
+    // desplaza el objetivo, y la rueda acerca o aleja
+    pub orbit_sensitivity: f32,  // radianes por píxel
+    pub pan_sensitivity: f32,    // fracción de la distancia por píxel
+    pub scroll_zoom_speed: f32,  // fracción de la distancia por paso de la rueda
+    // invierten el movimiento horizontal/vertical del ratón (órbita y vuelo libre)
+    pub invert_x: bool,
+    pub invert_y: bool,
+
+    // vuelo libre: orientación completa (con roll) y velocidad en espacio mundo. La posición
+    // se integra en pasos fijos y `eye` se interpola entre los dos últimos
+    pub orientation: Quaternion,
+    pub velocity: Vector3,
+    fly_position: Vector3,
+    previous_fly_position: Vector3,
+    fly_thrust: Vector3,  // dirección pedida con el teclado en este cuadro
+    pub fly_acceleration: f32,   // unidades/s²
+    pub fly_max_speed: f32,      // unidades/s
+    pub fly_damping: f32,        // frenado por segundo cuando no se acelera
+    pub mouse_sensitivity: f32,  // radianes por píxel
+    pub roll_speed: f32,         // radianes/s
+    pub look_speed: f32,         // radianes/s con el stick de mirar a fondo
+
+    pub chase: ChaseParams,
+    chase_state: ChaseState,
+
+    transition: Option<CameraTransition>,
 }
 
 impl Camera {
@@ -38,18 +252,155 @@ impl Camera {
         let max_distance = 100.0;
 
         Camera {
+            mode: CameraMode::Orbit,
+            projection: Projection::default(),
+            min_fov: 20.0_f32.to_radians(),
+            max_fov: 120.0_f32.to_radians(),
             eye,
             target,
             up,
             yaw,
             pitch,
             distance: distance.clamp(min_distance, max_distance),
-            rotation_speed: 0.05,
-            zoom_speed: 0.5,
-            pan_speed: 0.1,
+            rotation_speed: 3.0,
+            zoom_speed: 30.0,
+            pan_speed: 6.0,
             min_distance,
             max_distance,
+            orbit_sensitivity: 0.008,
+            pan_sensitivity: 0.0015,
+            scroll_zoom_speed: 0.1,
+            invert_x: false,
+            invert_y: false,
+            orientation: look_rotation(target - eye, up),
+            velocity: Vector3::zero(),
+            fly_position: eye,
+            previous_fly_position: eye,
+            fly_thrust: Vector3::zero(),
+            fly_acceleration: 20.0,
+            fly_max_speed: 8.0,
+            fly_damping: 4.0,
+            mouse_sensitivity: 0.003,
+            roll_speed: 1.5,
+            look_speed: 2.0,
+            chase: ChaseParams::default(),
+            chase_state: ChaseState::default(),
+            transition: None,
+        }
+    }
+
+    pub fn pose(&self) -> CameraPose {
+        CameraPose { target: self.target, distance: self.distance, yaw: self.yaw, pitch: self.pitch }
+    }
+
+    /// Vuela hasta `pose` en `duration` segundos en vez de saltar. El objetivo y la distancia
+    /// se interpolan con `easing` y la orientación con slerp, por el camino más corto.
+    /// Cualquier control del usuario interrumpe la transición.
+    pub fn fly_to(&mut self, pose: CameraPose, duration: f32, easing: Easing) {
+        let from = self.pose();
+        let from_rotation = from.rotation();
+        let mut to_rotation = pose.rotation();
+        // q y -q son la misma rotación: se elige la que queda más cerca
+        if from_rotation.x * to_rotation.x + from_rotation.y * to_rotation.y + from_rotation.z * to_rotation.z + from_rotation.w * to_rotation.w < 0.0 {
+            to_rotation = Quaternion::new(-to_rotation.x, -to_rotation.y, -to_rotation.z, -to_rotation.w);
+        }
+
+        self.transition = Some(CameraTransition {
+            from,
+            to: pose,
+            from_rotation,
+            to_rotation,
+            elapsed: 0.0,
+            duration: duration.max(1e-3),
+            easing,
+        });
+    }
+
+    /// Enfoca una esfera (un planeta o una luna): la mira desde unos radios de distancia,
+    /// con los mismos ángulos de ahora.
+    pub fn focus(&mut self, center: Vector3, radius: f32, duration: f32, easing: Easing) {
+        let pose = CameraPose {
+            target: center,
+            distance: (radius * 4.0).clamp(self.min_distance, self.max_distance),
+            ..self.pose()
+        };
+        self.fly_to(pose, duration, easing);
+    }
+
+    pub fn is_transitioning(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    // Avanza la transición en curso `dt` segundos
+    fn update_transition(&mut self, dt: f32) {
+        let Some(transition) = &mut self.transition else {
+            return;
+        };
+        transition.elapsed += dt;
+        let t = (transition.elapsed / transition.duration).min(1.0);
+        let k = transition.easing.apply(t);
+
+        let (from, to) = (transition.from, transition.to);
+        let rotation = transition.from_rotation.slerp(transition.to_rotation, k).normalized();
+        let finished = t >= 1.0;
+
+        self.target = from.target.lerp(to.target, k);
+        self.distance = from.distance + (to.distance - from.distance) * k;
+        if finished {
+            self.yaw = to.yaw;
+            self.pitch = to.pitch;
+            self.transition = None;
+        } else {
+            let to_eye = -Vector3::new(0.0, 0.0, -1.0).rotate_by(rotation);
+            self.pitch = to_eye.y.clamp(-1.0, 1.0).asin();
+            self.yaw = to_eye.z.atan2(to_eye.x);
         }
+        self.update_eye_position();
+    }
+
+    /// Cambia entre órbita y vuelo libre sin mover la vista: el vuelo libre arranca con la
+    /// orientación actual, y al volver a la órbita el objetivo queda delante de la cámara a
+    /// la misma distancia. La órbita no tiene roll, así que al volver se nivela el horizonte.
+    pub fn set_mode(&mut self, mode: CameraMode, window: &mut RaylibHandle) {
+        if mode == self.mode {
+            return;
+        }
+
+        self.transition = None;
+        match mode {
+            CameraMode::FreeFly => {
+                self.orientation = look_rotation(self.target - self.eye, self.up);
+                self.velocity = Vector3::zero();
+                self.fly_position = self.eye;
+                self.previous_fly_position = self.eye;
+                self.fly_thrust = Vector3::zero();
+                window.disable_cursor();
+            }
+            CameraMode::Orbit => {
+                // los otros modos mantienen `target` delante de la cámara
+                let offset = self.eye - self.target;
+                self.distance = offset.length().max(1e-4);
+                self.up = Vector3::new(0.0, 1.0, 0.0);
+                let direction = offset / self.distance;
+                self.pitch = direction.y.clamp(-1.0, 1.0).asin();
+                self.yaw = direction.z.atan2(direction.x);
+                self.update_eye_position();
+                window.enable_cursor();
+            }
+            CameraMode::Chase => {
+                self.chase_state = ChaseState::default();
+                window.enable_cursor();
+            }
+        }
+        self.mode = mode;
+    }
+
+    pub fn toggle_mode(&mut self, window: &mut RaylibHandle) {
+        let mode = match self.mode {
+            CameraMode::FreeFly => CameraMode::Orbit,
+            _ => CameraMode::FreeFly,
+        };
+        self.set_mode(mode, window);
     }
 
     /// Update camera eye position based on yaw, pitch, and distance
@@ -74,40 +425,240 @@ impl Camera {
         self.eye.z = self.target.z + d * cos_pitch * sin_yaw;
     }
 
+    /// Pone la cámara en `eye` mirando a `target` (sin roll), sea cual sea el modo, y
+    /// deja yaw/pitch/distance coherentes para seguir orbitando desde ahí.
+    pub fn look_from(&mut self, eye: Vector3, target: Vector3) {
+        self.transition = None;
+        self.eye = eye;
+        self.target = target;
+        self.up = Vector3::new(0.0, 1.0, 0.0);
+
+        let offset = eye - target;
+        self.distance = offset.length().max(1e-4);
+        let direction = offset / self.distance;
+        self.pitch = direction.y.clamp(-1.0, 1.0).asin();
+        self.yaw = direction.z.atan2(direction.x);
+
+        self.orientation = look_rotation(target - eye, self.up);
+        self.velocity = Vector3::zero();
+        self.fly_position = eye;
+        self.previous_fly_position = eye;
+    }
+
     pub fn get_view_matrix(&self) -> Matrix {
         create_view_matrix(self.eye, self.target, self.up)
     }
 
-    pub fn process_input(&mut self, window: &RaylibHandle) {
-        // Rotation controls (yaw)
-        if window.is_key_down(KeyboardKey::KEY_A) {
-            self.yaw += self.rotation_speed;
-            self.update_eye_position();
+    pub fn get_projection_matrix(&self, aspect: f32) -> Matrix {
+        self.projection.matrix(aspect, self.distance)
+    }
+
+    /// Planos de lo que ve la cámara, en espacio mundo.
+    pub fn frustum(&self, aspect: f32) -> Frustum {
+        Frustum::from_matrix(&multiply_matrices(&self.get_projection_matrix(aspect), &self.get_view_matrix()))
+    }
+
+    /// Cambia el FOV en `delta` radianes, dentro de `min_fov`/`max_fov`.
+    pub fn adjust_fov(&mut self, delta: f32) {
+        self.projection.fov_y = (self.projection.fov_y + delta).clamp(self.min_fov, self.max_fov);
+    }
+
+    /// Lee los controles una vez por cuadro; `dt` es el tiempo real del cuadro en segundos.
+    /// El ratón se mide en píxeles y el teclado en unidades por segundo.
+    pub fn process_input(&mut self, input: &Input, dt: f32) {
+        match self.mode {
+            CameraMode::Orbit => {
+                // si los controles movieron la cámara, el usuario manda y la transición se corta
+                let before = self.pose();
+                self.process_orbit_input(input, dt);
+                let after = self.pose();
+                let touched = before.target != after.target
+                    || before.distance != after.distance
+                    || before.yaw != after.yaw
+                    || before.pitch != after.pitch;
+                if touched {
+                    self.transition = None;
+                } else {
+                    self.update_transition(dt);
+                }
+            }
+            CameraMode::FreeFly => self.process_free_fly_input(input, dt),
+            // la persecución se mueve sola con `update_chase`
+            CameraMode::Chase => {}
+        }
+    }
+
+    /// Vuelo libre: el ratón (o el stick de mirar) gira la cámara en sus propios ejes, las
+    /// acciones de vuelo avanzan, se desplazan de lado, suben y bajan, y las de roll ruedan.
+    /// El movimiento se integra después en `fixed_update`.
+    fn process_free_fly_input(&mut self, input: &Input, dt: f32) {
+        // rotaciones en los ejes locales: se multiplican a la derecha de la orientación
+        let mouse = self.mouse_delta(input);
+        let roll = (input.value(Action::RollLeft) - input.value(Action::RollRight)) * self.roll_speed * dt;
+        let look_x = (input.value(Action::LookRight) - input.value(Action::LookLeft)) * self.look_speed * dt;
+        let look_y = (input.value(Action::LookDown) - input.value(Action::LookUp)) * self.look_speed * dt;
+        let rotation = Quaternion::from_axis_angle(Vector3::new(0.0, 1.0, 0.0), -mouse.x * self.mouse_sensitivity - look_x)
+            * Quaternion::from_axis_angle(Vector3::new(1.0, 0.0, 0.0), -mouse.y * self.mouse_sensitivity - look_y)
+            * Quaternion::from_axis_angle(Vector3::new(0.0, 0.0, -1.0), roll);
+        self.orientation = (self.orientation * rotation).normalized();
+
+        let forward = Vector3::new(0.0, 0.0, -1.0).rotate_by(self.orientation);
+        let right = Vector3::new(1.0, 0.0, 0.0).rotate_by(self.orientation);
+        let up = Vector3::new(0.0, 1.0, 0.0).rotate_by(self.orientation);
+
+        // con un stick a medias se empuja a medias; nunca más que con una tecla
+        let thrust = forward * (input.value(Action::FlyForward) - input.value(Action::FlyBack))
+            + right * (input.value(Action::FlyRight) - input.value(Action::FlyLeft))
+            + up * (input.value(Action::FlyUp) - input.value(Action::FlyDown));
+
+        self.fly_thrust = if thrust.length() > 1.0 { thrust.normalized() } else { thrust };
+    }
+
+    /// Paso fijo de la simulación: la velocidad acelera hacia la dirección pedida y se frena
+    /// sola al soltar las teclas.
+    pub fn fixed_update(&mut self, step: f32) {
+        if self.mode != CameraMode::FreeFly {
+            return;
+        }
+
+        if self.fly_thrust.length() > 0.0 {
+            self.velocity += self.fly_thrust * (self.fly_acceleration * step);
+        } else {
+            self.velocity = self.velocity * (1.0 - self.fly_damping * step).max(0.0);
+        }
+        let speed = self.velocity.length();
+        if speed > self.fly_max_speed {
+            self.velocity = self.velocity * (self.fly_max_speed / speed);
+        }
+
+        self.previous_fly_position = self.fly_position;
+        self.fly_position += self.velocity * step;
+    }
+
+    /// Posición para dibujar: `alpha` es la fracción del paso fijo que ya pasó desde el
+    /// último `fixed_update`.
+    pub fn interpolate(&mut self, alpha: f32) {
+        if self.mode != CameraMode::FreeFly {
+            return;
         }
-        if window.is_key_down(KeyboardKey::KEY_D) {
-            self.yaw -= self.rotation_speed;
+
+        self.eye = self.previous_fly_position.lerp(self.fly_position, alpha);
+        // target y up siguen a la orientación para que la matriz de vista no cambie de forma
+        let forward = Vector3::new(0.0, 0.0, -1.0).rotate_by(self.orientation);
+        self.target = self.eye + forward * self.distance;
+        self.up = Vector3::new(0.0, 1.0, 0.0).rotate_by(self.orientation);
+    }
+
+    /// Persecución: lleva la cámara detrás de `subject` (su matriz de modelo) con resortes
+    /// críticamente amortiguados para la posición, el punto mirado y el "arriba". El brazo
+    /// se orienta según la dirección de avance del objeto, o con sus propios ejes si está
+    /// quieto, y se mira un poco por delante según su velocidad.
+    pub fn update_chase(&mut self, subject: &Matrix, dt: f32) {
+        if dt <= 0.0 {
+            return;
+        }
+        let params = self.chase;
+        let position = Vector3::new(subject.m12, subject.m13, subject.m14);
+        let x_axis = Vector3::new(subject.m0, subject.m1, subject.m2);
+        let y_axis = Vector3::new(subject.m4, subject.m5, subject.m6);
+        let z_axis = Vector3::new(subject.m8, subject.m9, subject.m10);
+        let scale = x_axis.length();
+
+        let state = &mut self.chase_state;
+        let velocity = match state.last_subject {
+            Some(last) => (position - last) / dt,
+            None => Vector3::zero(),
+        };
+        let first_frame = state.last_subject.is_none();
+        state.last_subject = Some(position);
+
+        let subject_up = y_axis.normalized();
+        let world_up = Vector3::new(0.0, 1.0, 0.0);
+        let up_hint = if params.lock_roll { subject_up } else { world_up };
+
+        // base del brazo: atrás es contra la dirección de avance
+        let back = if velocity.length() > 1e-3 { -velocity.normalized() } else { z_axis.normalized() };
+        let right = up_hint.cross(back);
+        let right = if right.length() > 1e-4 { right.normalized() } else { x_axis.normalized() };
+        let up = back.cross(right);
+
+        let desired_eye = position + (right * params.offset.x + up * params.offset.y + back * params.offset.z) * scale;
+        let desired_look = position + velocity * params.look_ahead;
+
+        if first_frame {
+            // el primer cuadro arranca desde donde estaba la cámara, sin velocidad
+            state.eye_velocity = Vector3::zero();
+            state.look_velocity = Vector3::zero();
+            state.up_velocity = Vector3::zero();
+        }
+        self.eye = smooth_damp(self.eye, desired_eye, &mut state.eye_velocity, params.position_stiffness, dt);
+        self.target = smooth_damp(self.target, desired_look, &mut state.look_velocity, params.look_stiffness, dt);
+        let smoothed_up = smooth_damp(self.up, up_hint, &mut state.up_velocity, params.look_stiffness, dt);
+        self.up = if smoothed_up.length() > 1e-4 { smoothed_up.normalized() } else { up_hint };
+        // la ortográfica toma el tamaño visible de la distancia al punto mirado
+        self.distance = (self.eye - self.target).length();
+    }
+
+    // Movimiento del ratón en este cuadro, con los ejes invertidos si corresponde
+    fn mouse_delta(&self, input: &Input) -> Vector2 {
+        let delta = input.mouse_delta;
+        Vector2::new(
+            if self.invert_x { -delta.x } else { delta.x },
+            if self.invert_y { -delta.y } else { delta.y },
+        )
+    }
+
+    /// Ratón en la órbita: se "agarra" la escena, así que arrastrar a la derecha la gira a la
+    /// derecha. El desplazamiento y el zoom son proporcionales a la distancia, para que se
+    /// sientan igual de cerca que de lejos.
+    fn process_orbit_mouse(&mut self, input: &Input) {
+        let delta = self.mouse_delta(input);
+
+        if input.is_down(Action::OrbitDrag) {
+            self.yaw += delta.x * self.orbit_sensitivity;
+            self.pitch += delta.y * self.orbit_sensitivity;
             self.update_eye_position();
         }
 
-        // Rotation controls (pitch)
-        if window.is_key_down(KeyboardKey::KEY_W) {
-            self.pitch += self.rotation_speed;
+        if input.is_down(Action::PanDrag) {
+            // ejes de la cámara: el objetivo se mueve en el plano perpendicular a la vista
+            let forward = (self.target - self.eye).normalized();
+            let right = forward.cross(self.up).normalized();
+            let up = right.cross(forward);
+            let scale = self.distance * self.pan_sensitivity;
+            self.target = self.target - right * (delta.x * scale) + up * (delta.y * scale);
             self.update_eye_position();
         }
-        if window.is_key_down(KeyboardKey::KEY_S) {
-            self.pitch -= self.rotation_speed;
+
+        let wheel = input.mouse_wheel;
+        if wheel != 0.0 {
+            self.distance -= wheel * self.scroll_zoom_speed * self.distance;
             self.update_eye_position();
         }
+    }
 
-        // Zoom controls (distance from target) - arrow keys
-        if window.is_key_down(KeyboardKey::KEY_UP) {
-            self.distance -= self.zoom_speed;
-            // clamp inmediatamente para evitar valores muy pequeños
-            self.distance = self.distance.clamp(self.min_distance, self.max_distance);
+    /// Órbita con acciones: cada una se escala por su valor, así un stick a medias gira
+    /// a media velocidad y una tecla a la velocidad completa.
+    fn process_orbit_input(&mut self, input: &Input, dt: f32) {
+        self.process_orbit_mouse(input);
+        let rotation = self.rotation_speed * dt;
+        let zoom = self.zoom_speed * dt;
+        let pan = self.pan_speed * dt;
+
+        // Rotation controls (yaw y pitch)
+        let yaw = input.value(Action::OrbitLeft) - input.value(Action::OrbitRight);
+        let pitch = input.value(Action::OrbitUp) - input.value(Action::OrbitDown);
+        if yaw != 0.0 || pitch != 0.0 {
+            self.yaw += yaw * rotation;
+            self.pitch += pitch * rotation;
             self.update_eye_position();
         }
-        if window.is_key_down(KeyboardKey::KEY_DOWN) {
-            self.distance += self.zoom_speed;
+
+        // Zoom controls (distance from target)
+        let zoom_amount = input.value(Action::ZoomOut) - input.value(Action::ZoomIn);
+        if zoom_amount != 0.0 {
+            self.distance += zoom_amount * zoom;
+            // clamp inmediatamente para evitar valores muy pequeños
             self.distance = self.distance.clamp(self.min_distance, self.max_distance);
             self.update_eye_position();
         }
@@ -133,37 +684,18 @@ impl Camera {
             -forward_normalized.x,
         );
 
-        // Q/E keys for horizontal panning
-        if window.is_key_down(KeyboardKey::KEY_Q) {
-            self.target.x += right.x * self.pan_speed;
-            self.target.z += right.z * self.pan_speed;
-            self.update_eye_position();
-        }
-        if window.is_key_down(KeyboardKey::KEY_E) {
-            self.target.x -= right.x * self.pan_speed;
-            self.target.z -= right.z * self.pan_speed;
-            self.update_eye_position();
-        }
-
-        // Left/Right arrow keys for horizontal panning
-        if window.is_key_down(KeyboardKey::KEY_LEFT) {
-            self.target.x += right.x * self.pan_speed;
-            self.target.z += right.z * self.pan_speed;
-            self.update_eye_position();
-        }
-        if window.is_key_down(KeyboardKey::KEY_RIGHT) {
-            self.target.x -= right.x * self.pan_speed;
-            self.target.z -= right.z * self.pan_speed;
+        // Horizontal panning
+        let horizontal = input.value(Action::PanLeft) - input.value(Action::PanRight);
+        if horizontal != 0.0 {
+            self.target.x += right.x * pan * horizontal;
+            self.target.z += right.z * pan * horizontal;
             self.update_eye_position();
         }
 
         // Vertical panning
-        if window.is_key_down(KeyboardKey::KEY_R) {
-            self.target.y += self.pan_speed;
-            self.update_eye_position();
-        }
-        if window.is_key_down(KeyboardKey::KEY_F) {
-            self.target.y -= self.pan_speed;
+        let vertical = input.value(Action::PanUp) - input.value(Action::PanDown);
+        if vertical != 0.0 {
+            self.target.y += pan * vertical;
             self.update_eye_position();
         }
     }
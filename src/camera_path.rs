@@ -0,0 +1,255 @@
+// camera_path.rs
+#![allow(dead_code)]
+
+use raylib::prelude::*;
+use crate::camera::{Camera, Easing};
+
+/// Una pose grabada de la cámara en el segundo `time` del recorrido.
+#[derive(Clone, Copy, Debug)]
+pub struct Keyframe {
+    pub time: f32,
+    pub eye: Vector3,
+    pub target: Vector3,
+    pub fov_y: f32,  // radianes
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PathInterpolation {
+    // pasa por todas las poses grabadas
+    CatmullRom,
+    // las poses son puntos de control de una sola curva: más suave, pero solo pasa por
+    // la primera y la última
+    Bezier,
+}
+
+/// Recorrido de cámara hecho de poses grabadas, para videos de demostración.
+#[derive(Clone, Debug)]
+pub struct CameraPath {
+    pub keyframes: Vec<Keyframe>,
+    pub interpolation: PathInterpolation,
+    pub easing: Easing,  // se aplica al tiempo del recorrido completo
+}
+
+// Valores interpolables de una pose
+#[derive(Clone, Copy)]
+struct PoseValues {
+    eye: Vector3,
+    target: Vector3,
+    fov_y: f32,
+}
+
+impl PoseValues {
+    fn of(keyframe: &Keyframe) -> Self {
+        PoseValues { eye: keyframe.eye, target: keyframe.target, fov_y: keyframe.fov_y }
+    }
+
+    fn lerp(&self, other: &PoseValues, t: f32) -> PoseValues {
+        PoseValues {
+            eye: self.eye.lerp(other.eye, t),
+            target: self.target.lerp(other.target, t),
+            fov_y: self.fov_y + (other.fov_y - self.fov_y) * t,
+        }
+    }
+}
+
+// Catmull-Rom uniforme entre p1 y p2
+fn catmull_rom(p0: f32, p1: f32, p2: f32, p3: f32, t: f32) -> f32 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * (2.0 * p1 + (p2 - p0) * t + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2 + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+}
+
+fn catmull_rom_vector(p0: Vector3, p1: Vector3, p2: Vector3, p3: Vector3, t: f32) -> Vector3 {
+    Vector3::new(
+        catmull_rom(p0.x, p1.x, p2.x, p3.x, t),
+        catmull_rom(p0.y, p1.y, p2.y, p3.y, t),
+        catmull_rom(p0.z, p1.z, p2.z, p3.z, t),
+    )
+}
+
+fn parse_values(line: &str) -> Result<Keyframe, String> {
+    let values: Vec<f32> = line
+        .split_whitespace()
+        .map(|token| token.parse::<f32>().map_err(|_| format!("'{}' no es un número", token)))
+        .collect::<Result<_, _>>()?;
+    match values.as_slice() {
+        [time, ex, ey, ez, tx, ty, tz, fov] => Ok(Keyframe {
+            time: *time,
+            eye: Vector3::new(*ex, *ey, *ez),
+            target: Vector3::new(*tx, *ty, *tz),
+            fov_y: fov.to_radians(),
+        }),
+        _ => Err(format!("se esperaban 8 números y hay {}", values.len())),
+    }
+}
+
+const EASINGS: [(&str, Easing); 4] = [
+    ("linear", Easing::Linear),
+    ("in_out_quad", Easing::EaseInOutQuad),
+    ("in_out_cubic", Easing::EaseInOutCubic),
+    ("out_expo", Easing::EaseOutExpo),
+];
+
+fn easing_by_name(name: &str) -> Option<Easing> {
+    EASINGS.iter().find(|(n, _)| *n == name).map(|(_, easing)| *easing)
+}
+
+fn easing_name(easing: Easing) -> &'static str {
+    EASINGS.iter().find(|(_, e)| *e == easing).map(|(n, _)| *n).unwrap_or("linear")
+}
+
+impl Default for CameraPath {
+    fn default() -> Self {
+        CameraPath {
+            keyframes: Vec::new(),
+            interpolation: PathInterpolation::CatmullRom,
+            easing: Easing::Linear,
+        }
+    }
+}
+
+impl CameraPath {
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map(|k| k.time).unwrap_or(0.0)
+    }
+
+    /// Agrega la pose actual de la cámara en el segundo `time` (se mantienen ordenadas).
+    pub fn record(&mut self, camera: &Camera, time: f32) {
+        let keyframe = Keyframe { time, eye: camera.eye, target: camera.target, fov_y: camera.projection.fov_y };
+        let index = self.keyframes.partition_point(|k| k.time <= time);
+        self.keyframes.insert(index, keyframe);
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        CameraPath::parse(&text).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    /// Lee un recorrido desde texto: una pose por línea.
+    ///
+    /// ```text
+    /// # [catmull_rom | bezier]
+    /// # [ease linear | in_out_quad | in_out_cubic | out_expo]
+    /// # <tiempo> <eye x y z> <target x y z> <fov en grados>
+    /// bezier
+    /// ease in_out_cubic
+    /// 0.0  0 0 5   0 0 0   60
+    /// 2.5  3 1 3   0 0 0   45
+    /// ```
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut path = CameraPath::default();
+
+        for (number, raw) in text.lines().enumerate() {
+            let line = raw.split('#').next().unwrap_or("").trim();
+            match line {
+                "" => continue,
+                "catmull_rom" => path.interpolation = PathInterpolation::CatmullRom,
+                "bezier" => path.interpolation = PathInterpolation::Bezier,
+                _ if line.starts_with("ease") => {
+                    let name = line.trim_start_matches("ease").trim();
+                    path.easing = easing_by_name(name)
+                        .ok_or_else(|| format!("línea {}: easing desconocido '{}'", number + 1, name))?;
+                }
+                _ => {
+                    let keyframe = parse_values(line).map_err(|e| format!("línea {}: {}", number + 1, e))?;
+                    path.keyframes.push(keyframe);
+                }
+            }
+        }
+
+        path.keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Ok(path)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        if let Some(dir) = std::path::Path::new(path).parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", path, e))?;
+        }
+
+        let mut text = String::from("# <tiempo> <eye x y z> <target x y z> <fov en grados>\n");
+        text.push_str(match self.interpolation {
+            PathInterpolation::CatmullRom => "catmull_rom\n",
+            PathInterpolation::Bezier => "bezier\n",
+        });
+        text.push_str(&format!("ease {}\n", easing_name(self.easing)));
+        for k in &self.keyframes {
+            text.push_str(&format!(
+                "{:.3}  {:.4} {:.4} {:.4}  {:.4} {:.4} {:.4}  {:.2}\n",
+                k.time, k.eye.x, k.eye.y, k.eye.z, k.target.x, k.target.y, k.target.z, k.fov_y.to_degrees(),
+            ));
+        }
+        std::fs::write(path, text).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    // Pose en el segundo `time`, ya con el easing aplicado; None si no hay poses
+    fn sample_values(&self, time: f32) -> Option<PoseValues> {
+        let first = self.keyframes.first()?;
+        let duration = self.duration() - first.time;
+        if self.keyframes.len() == 1 || duration <= 0.0 {
+            return Some(PoseValues::of(first));
+        }
+
+        let progress = self.easing.apply((time - first.time) / duration);
+        let time = first.time + progress * duration;
+
+        Some(match self.interpolation {
+            PathInterpolation::CatmullRom => {
+                let count = self.keyframes.len();
+                let segment = self.keyframes.partition_point(|k| k.time <= time).clamp(1, count - 1) - 1;
+                let (k1, k2) = (&self.keyframes[segment], &self.keyframes[segment + 1]);
+                let k0 = &self.keyframes[segment.saturating_sub(1)];
+                let k3 = &self.keyframes[(segment + 2).min(count - 1)];
+                let span = (k2.time - k1.time).max(1e-6);
+                let t = ((time - k1.time) / span).clamp(0.0, 1.0);
+
+                PoseValues {
+                    eye: catmull_rom_vector(k0.eye, k1.eye, k2.eye, k3.eye, t),
+                    target: catmull_rom_vector(k0.target, k1.target, k2.target, k3.target, t),
+                    fov_y: catmull_rom(k0.fov_y, k1.fov_y, k2.fov_y, k3.fov_y, t),
+                }
+            }
+            PathInterpolation::Bezier => {
+                // de Casteljau sobre todas las poses
+                let mut points: Vec<PoseValues> = self.keyframes.iter().map(PoseValues::of).collect();
+                while points.len() > 1 {
+                    points = points.windows(2).map(|pair| pair[0].lerp(&pair[1], progress)).collect();
+                }
+                points[0]
+            }
+        })
+    }
+}
+
+/// Reproduce un recorrido moviendo la cámara. Avanza con el `dt` que se le pase, así que
+/// con un paso fijo por cuadro el resultado es el mismo en cada ejecución.
+pub struct PathPlayer {
+    pub path: CameraPath,
+    pub time: f32,
+    pub looping: bool,
+}
+
+impl PathPlayer {
+    pub fn new(path: CameraPath) -> Self {
+        let time = path.keyframes.first().map(|k| k.time).unwrap_or(0.0);
+        PathPlayer { path, time, looping: false }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        !self.looping && self.time >= self.path.duration()
+    }
+
+    /// Avanza `dt` segundos y deja la cámara en la pose correspondiente.
+    pub fn update(&mut self, camera: &mut Camera, dt: f32) {
+        self.time += dt;
+        if self.looping && self.time > self.path.duration() {
+            let start = self.path.keyframes.first().map(|k| k.time).unwrap_or(0.0);
+            let length = (self.path.duration() - start).max(1e-6);
+            self.time = start + (self.time - start) % length;
+        }
+
+        if let Some(pose) = self.path.sample_values(self.time.min(self.path.duration())) {
+            camera.look_from(pose.eye, pose.target);
+            camera.projection.fov_y = pose.fov_y;
+        }
+    }
+}
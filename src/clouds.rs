@@ -0,0 +1,109 @@
+// clouds.rs
+#![allow(dead_code)]
+
+use raylib::prelude::*;
+use noise::{Fbm, MultiFractal, NoiseFn, Perlin};
+use crate::Uniforms;
+use crate::ambient::ambient_light;
+use crate::fragment::Fragment;
+use crate::matrix::multiply_matrix_vector4;
+use crate::shaders::smoothstep;
+
+thread_local! {
+    static CLOUD_NOISE: Fbm<Perlin> = Fbm::<Perlin>::new(11).set_octaves(5);
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct CloudParams {
+    pub altitude: f32,         // escala de la capa respecto al planeta (1.0 = misma superficie)
+    pub rotation_speed: f32,   // radianes por segundo alrededor de Y, independiente del planeta
+    pub coverage: f32,         // fracción aproximada del cielo cubierta, en [0, 1]
+    pub softness: f32,         // ancho del borde de las nubes
+    pub frequency: f32,
+    pub drift_speed: f32,      // qué tan rápido cambia la forma de las nubes
+    pub shadow_strength: f32,  // cuánto oscurece la sombra sobre la superficie
+    pub shadow_offset: f32,    // desplazamiento hacia la luz al muestrear la sombra
+    pub color: Vector3,
+}
+
+impl Default for CloudParams {
+    fn default() -> Self {
+        CloudParams {
+            altitude: 1.03,
+            rotation_speed: 0.18,
+            coverage: 0.45,
+            softness: 0.12,
+            frequency: 2.5,
+            drift_speed: 0.03,
+            shadow_strength: 0.55,
+            shadow_offset: 0.04,
+            color: Vector3::new(1.0, 1.0, 1.0),
+        }
+    }
+}
+
+impl CloudParams {
+    /// Ángulo de rotación de la capa en el instante `time`. Lo usa tanto la matriz
+    /// de modelo de la capa como la sombra, para que ambas coincidan.
+    pub fn rotation_angle(&self, time: f32) -> f32 {
+        time * self.rotation_speed
+    }
+}
+
+/// Cobertura de nubes en [0, 1] para una dirección en el espacio de la capa de nubes.
+pub fn cloud_coverage(dir: Vector3, time: f32, clouds: &CloudParams) -> f32 {
+    let p = dir * clouds.frequency;
+    let raw = CLOUD_NOISE.with(|n| n.get([p.x as f64, p.y as f64, p.z as f64, (time * clouds.drift_speed) as f64])) as f32;
+    let value = (raw + 1.0) * 0.5;
+
+    let threshold = 1.0 - clouds.coverage;
+    smoothstep(threshold - clouds.softness, threshold + clouds.softness, value)
+}
+
+/// Factor de sombra (1 = sin sombra) que proyecta la capa de nubes sobre un punto de la
+/// superficie. `surface_dir` y `light_dir` están en espacio mundo, relativos al centro del planeta.
+pub fn cloud_shadow(surface_dir: Vector3, light_dir: Vector3, uniforms: &Uniforms) -> f32 {
+    let clouds = &uniforms.clouds;
+
+    // La nube que tapa la luz está un poco desplazada hacia la luz
+    let sample = (surface_dir + light_dir * clouds.shadow_offset).normalized();
+
+    // Pasamos al espacio de la capa deshaciendo su rotación en Y
+    let (sin_a, cos_a) = (-clouds.rotation_angle(uniforms.time)).sin_cos();
+    let local = Vector3::new(
+        cos_a * sample.x + sin_a * sample.z,
+        sample.y,
+        -sin_a * sample.x + cos_a * sample.z,
+    );
+
+    1.0 - cloud_coverage(local, uniforms.time, clouds) * clouds.shadow_strength
+}
+
+/// Shader de la capa de nubes; devuelve color en xyz y alpha en w.
+pub fn cloud_fragment_shader(fragment: &Fragment, uniforms: &Uniforms) -> Vector4 {
+    let clouds = &uniforms.clouds;
+    let dir = fragment.vertex_position.normalized();
+
+    // Descartamos las caras traseras de la capa: si no, el borde del planeta se mezcla dos veces
+    let world = multiply_matrix_vector4(&uniforms.model_matrix, &Vector4::new(fragment.vertex_position.x, fragment.vertex_position.y, fragment.vertex_position.z, 1.0));
+    let view_pos = multiply_matrix_vector4(&uniforms.view_matrix, &world);
+    let normal_world = multiply_matrix_vector4(&uniforms.model_matrix, &Vector4::new(dir.x, dir.y, dir.z, 0.0));
+    let normal_view = multiply_matrix_vector4(&uniforms.view_matrix, &normal_world);
+    let facing = -(normal_view.x * view_pos.x + normal_view.y * view_pos.y + normal_view.z * view_pos.z);
+    if facing <= 0.0 {
+        return Vector4::new(0.0, 0.0, 0.0, 0.0);
+    }
+
+    let alpha = cloud_coverage(dir, uniforms.time, clouds);
+
+    // Iluminación lambertiana simple para que el lado nocturno no tenga nubes blancas
+    let world_pos = Vector3::new(world.x, world.y, world.z);
+    let normal = Vector3::new(normal_world.x, normal_world.y, normal_world.z).normalized();
+    let mut diffuse = Vector3::zero();
+    for (light_dir, radiance) in uniforms.incident_lights(world_pos, normal) {
+        diffuse += radiance * normal.dot(light_dir).max(0.0);
+    }
+    let color = clouds.color * (diffuse * 0.92 + ambient_light(normal, uniforms));
+
+    Vector4::new(color.x, color.y, color.z, alpha)
+}
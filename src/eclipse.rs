@@ -0,0 +1,79 @@
+// eclipse.rs
+#![allow(dead_code)]
+
+use raylib::prelude::*;
+use std::f32::consts::PI;
+use crate::light::{Light, LightKind};
+
+/// Una esfera que puede tapar la luz a otros cuerpos (planetas y lunas). Las sombras entre
+/// esferas se calculan de forma analítica en vez de con los shadow maps.
+#[derive(Clone, Copy, Debug)]
+pub struct Occluder {
+    pub center: Vector3,
+    pub radius: f32,
+}
+
+impl Occluder {
+    /// Esfera de un objeto a partir de su matriz de modelo y el radio de la malla.
+    pub fn from_model_matrix(model_matrix: &Matrix, mesh_radius: f32) -> Self {
+        let scale = Vector3::new(model_matrix.m0, model_matrix.m1, model_matrix.m2).length();
+        Occluder {
+            center: Vector3::new(model_matrix.m12, model_matrix.m13, model_matrix.m14),
+            radius: mesh_radius * scale,
+        }
+    }
+}
+
+// Área de la intersección de dos discos de radios `a` y `b` con centros a distancia `d`
+fn disc_overlap(a: f32, b: f32, d: f32) -> f32 {
+    if d >= a + b {
+        return 0.0;
+    }
+    if d <= (a - b).abs() {
+        let r = a.min(b);
+        return PI * r * r;
+    }
+    let alpha = ((d * d + a * a - b * b) / (2.0 * d * a)).clamp(-1.0, 1.0).acos();
+    let beta = ((d * d + b * b - a * a) / (2.0 * d * b)).clamp(-1.0, 1.0).acos();
+    let kite = 0.5 * ((-d + a + b) * (d + a - b) * (d - a + b) * (d + a + b)).max(0.0).sqrt();
+    a * a * alpha + b * b * beta - kite
+}
+
+/// Fracción del disco de `light` visible desde `point` con las esferas de `occluders` delante:
+/// 1 fuera de la sombra, 0 en la umbra y un valor intermedio en la penumbra. El tamaño
+/// angular de la luz (`Light::radius`) es lo que da el borde suave.
+pub fn eclipse_visibility(light: &Light, point: Vector3, occluders: &[Occluder]) -> f32 {
+    let (to_light, light_distance) = match light.kind {
+        LightKind::Directional => (-light.direction, f32::INFINITY),
+        _ => {
+            let offset = light.position - point;
+            (offset.normalized(), offset.length())
+        }
+    };
+    let light_angle = light.angular_radius(point);
+
+    let mut visible = 1.0;
+    for occluder in occluders {
+        let offset = occluder.center - point;
+        let distance = offset.length();
+        // el punto está sobre (o dentro de) esta esfera: es su propio cuerpo, no un eclipse.
+        // El radio es el del vértice más lejano, así que cubre toda la malla
+        if distance <= occluder.radius * 1.001 || distance >= light_distance {
+            continue;
+        }
+        let occluder_angle = (occluder.radius / distance).asin();
+        let separation = (offset / distance).dot(to_light).clamp(-1.0, 1.0).acos();
+
+        // luz puntual: sombra dura
+        if light_angle <= 1e-5 {
+            if separation < occluder_angle {
+                return 0.0;
+            }
+            continue;
+        }
+
+        let covered = disc_overlap(light_angle, occluder_angle, separation) / (PI * light_angle * light_angle);
+        visible *= (1.0 - covered).clamp(0.0, 1.0);
+    }
+    visible
+}
@@ -7,6 +7,11 @@ pub struct Fragment {
     pub position: Vector2,
     pub color: Vector3,
     pub depth: f32,
+    pub normal: Vector3,
+    pub vertex_position: Vector3,
+    pub tex_coords: Vector2,
+    pub tangent: Vector3,
+    pub bitangent: Vector3,
 }
 
 impl Fragment {
@@ -15,6 +20,26 @@ impl Fragment {
             position: Vector2::new(x, y),
             color,
             depth,
+            normal: Vector3::zero(),
+            vertex_position: Vector3::zero(),
+            tex_coords: Vector2::zero(),
+            tangent: Vector3::zero(),
+            bitangent: Vector3::zero(),
+        }
+    }
+
+    /// Fragmento con la normal interpolada y la posición en espacio objeto,
+    /// para shaders que dependen de la superficie y no solo de la pantalla.
+    pub fn new_with_surface(x: f32, y: f32, color: Vector3, depth: f32, normal: Vector3, vertex_position: Vector3) -> Self {
+        Fragment {
+            position: Vector2::new(x, y),
+            color,
+            depth,
+            normal,
+            vertex_position,
+            tex_coords: Vector2::zero(),
+            tangent: Vector3::zero(),
+            bitangent: Vector3::zero(),
         }
     }
 }
@@ -1,5 +1,6 @@
 // framebuffer.rs
 use raylib::prelude::*;
+use crate::viewport::Viewport;
 
 pub struct Framebuffer {
     pub width: i32,
@@ -8,6 +9,9 @@ pub struct Framebuffer {
     background_color: Color,
     current_color: Color,
     depth_buffer: Vec<f32>,
+    normal_buffer: Vec<Vector3>,
+    // solo se dibuja (y se lee profundidad y normales) dentro de este rectángulo
+    scissor: Viewport,
 }
 
 impl Framebuffer {
@@ -15,6 +19,7 @@ impl Framebuffer {
         let background_color = Color::WHITE;
         let color_buffer = Image::gen_image_color(width, height, background_color);
         let depth_buffer = vec![f32::INFINITY; (width * height) as usize];
+        let normal_buffer = vec![Vector3::zero(); (width * height) as usize];
         Framebuffer {
             width,
             height,
@@ -22,22 +27,56 @@ impl Framebuffer {
             background_color,
             current_color: Color::WHITE,
             depth_buffer,
+            normal_buffer,
+            scissor: Viewport::new(0, 0, width, height),
         }
     }
 
+    /// Limpia el rectángulo de recorte (toda la imagen si no hay recorte).
     pub fn clear(&mut self) {
-        self.color_buffer.clear_background(self.background_color);
-        self.depth_buffer.fill(f32::INFINITY);
+        let area = self.scissor;
+        if area == Viewport::new(0, 0, self.width, self.height) {
+            self.color_buffer.clear_background(self.background_color);
+            self.depth_buffer.fill(f32::INFINITY);
+            self.normal_buffer.fill(Vector3::zero());
+            return;
+        }
+
+        self.color_buffer.draw_rectangle(area.x, area.y, area.width, area.height, self.background_color);
+        for y in area.y..area.y + area.height {
+            let row = (y * self.width) as usize;
+            let (start, end) = (row + area.x as usize, row + (area.x + area.width) as usize);
+            self.depth_buffer[start..end].fill(f32::INFINITY);
+            self.normal_buffer[start..end].fill(Vector3::zero());
+        }
+    }
+
+    /// Recorta el dibujo a `viewport` (ajustado a la imagen) hasta el próximo `reset_scissor`.
+    pub fn set_scissor(&mut self, viewport: Viewport) {
+        self.scissor = viewport.intersection(&Viewport::new(0, 0, self.width, self.height));
+    }
+
+    pub fn reset_scissor(&mut self) {
+        self.scissor = Viewport::new(0, 0, self.width, self.height);
+    }
+
+    pub fn scissor(&self) -> Viewport {
+        self.scissor
+    }
+
+    /// El pixel está dentro de la imagen y del rectángulo de recorte.
+    pub fn in_scissor(&self, x: i32, y: i32) -> bool {
+        self.scissor.contains(x, y)
     }
 
     pub fn set_pixel(&mut self, x: i32, y: i32) {
-        if x >= 0 && x < self.width && y >= 0 && y < self.height {
+        if self.in_scissor(x, y) {
             self.color_buffer.draw_pixel(x, y, self.current_color);
         }
     }
     
     pub fn point(&mut self, x: i32, y: i32, depth: f32, color: Vector3) {
-        if x >= 0 && x < self.width && y >= 0 && y < self.height {
+        if self.in_scissor(x, y) {
             let index = (y * self.width + x) as usize;
 
             if depth < self.depth_buffer[index] {
@@ -53,14 +92,80 @@ impl Framebuffer {
         }
     }
     
+    /// Mezcla un color translúcido sobre lo que ya hay. Hace depth test pero no escribe
+    /// profundidad, para que lo que está detrás siga visible a través de él.
+    pub fn blend_point(&mut self, x: i32, y: i32, depth: f32, color: Vector3, alpha: f32) {
+        if self.in_scissor(x, y) {
+            let index = (y * self.width + x) as usize;
+            let alpha = alpha.clamp(0.0, 1.0);
+
+            if alpha > 0.0 && depth < self.depth_buffer[index] {
+                let current = self.color_buffer.get_color(x, y);
+                let blend = |dst: u8, src: f32| {
+                    (dst as f32 * (1.0 - alpha) + src.clamp(0.0, 1.0) * 255.0 * alpha) as u8
+                };
+                let pixel_color = Color::new(
+                    blend(current.r, color.x),
+                    blend(current.g, color.y),
+                    blend(current.b, color.z),
+                    255,
+                );
+                self.color_buffer.draw_pixel(x, y, pixel_color);
+            }
+        }
+    }
+
     pub fn get_pixel_color(&mut self, x: i32, y: i32) -> Option<Color> {
-        if x >= 0 && x < self.width && y >= 0 && y < self.height {
+        if self.in_scissor(x, y) {
             Some(self.color_buffer.get_color(x, y))
         } else {
             None
         }
     }
 
+    /// Como `point`, pero además guarda la normal del fragmento visible para los post pass.
+    pub fn point_with_normal(&mut self, x: i32, y: i32, depth: f32, color: Vector3, normal: Vector3) {
+        if self.in_scissor(x, y) {
+            let index = (y * self.width + x) as usize;
+
+            if depth < self.depth_buffer[index] {
+                self.point(x, y, depth, color);
+                self.normal_buffer[index] = normal;
+            }
+        }
+    }
+
+    /// Normal guardada en el pixel; cero si es fondo.
+    pub fn get_normal(&self, x: i32, y: i32) -> Vector3 {
+        if self.in_scissor(x, y) {
+            self.normal_buffer[(y * self.width + x) as usize]
+        } else {
+            Vector3::zero()
+        }
+    }
+
+    pub fn get_depth(&self, x: i32, y: i32) -> f32 {
+        if self.in_scissor(x, y) {
+            self.depth_buffer[(y * self.width + x) as usize]
+        } else {
+            f32::INFINITY
+        }
+    }
+
+    /// Suma un color al pixel existente (mezcla aditiva), sin tocar el depth buffer.
+    pub fn add_color(&mut self, x: i32, y: i32, color: Vector3) {
+        if self.in_scissor(x, y) {
+            let current = self.color_buffer.get_color(x, y);
+            let pixel_color = Color::new(
+                (current.r as f32 + color.x.max(0.0) * 255.0).min(255.0) as u8,
+                (current.g as f32 + color.y.max(0.0) * 255.0).min(255.0) as u8,
+                (current.b as f32 + color.z.max(0.0) * 255.0).min(255.0) as u8,
+                255,
+            );
+            self.color_buffer.draw_pixel(x, y, pixel_color);
+        }
+    }
+
     pub fn set_background_color(&mut self, color: Color) {
         self.background_color = color;
     }
@@ -69,6 +174,17 @@ impl Framebuffer {
         self.current_color = color;
     }
 
+    /// Marco de un pixel alrededor de `viewport`, para separar un recuadro de lo que tiene detrás.
+    pub fn draw_border(&mut self, viewport: Viewport, color: Color) {
+        let rect = Rectangle::new(viewport.x as f32, viewport.y as f32, viewport.width as f32, viewport.height as f32);
+        self.color_buffer.draw_rectangle_lines(rect, 1, color);
+    }
+
+    /// Texto sobre la imagen (estadísticas y avisos de depuración).
+    pub fn draw_text(&mut self, text: &str, x: i32, y: i32, font_size: i32, color: Color) {
+        self.color_buffer.draw_text(text, x, y, font_size, color);
+    }
+
     pub fn swap_buffers(&self, d: &mut RaylibHandle, thread: &RaylibThread) {
         if let Ok(texture) = d.load_texture_from_image(thread, &self.color_buffer) {
             let mut d = d.begin_drawing(thread);
@@ -0,0 +1,425 @@
+// input.rs
+#![allow(dead_code)]
+
+use raylib::prelude::*;
+use std::collections::HashMap;
+
+/// Acciones con nombre: la cámara y `main` preguntan por estas en vez de por teclas, y el
+/// archivo de configuración decide qué teclas, botones o ejes las disparan.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    // órbita
+    OrbitLeft,
+    OrbitRight,
+    OrbitUp,
+    OrbitDown,
+    ZoomIn,
+    ZoomOut,
+    PanLeft,
+    PanRight,
+    PanUp,
+    PanDown,
+    OrbitDrag,
+    PanDrag,
+    Select,
+    // vuelo libre
+    FlyForward,
+    FlyBack,
+    FlyLeft,
+    FlyRight,
+    FlyUp,
+    FlyDown,
+    RollLeft,
+    RollRight,
+    LookLeft,
+    LookRight,
+    LookUp,
+    LookDown,
+    // modos de shader 1 a 10, y el siguiente/anterior
+    Shader(u8),
+    NextShader,
+    PreviousShader,
+    // escena y cámara
+    ToggleShip,
+    ToggleLightingModel,
+    CycleSsao,
+    SsaoDebug,
+    CycleAmbient,
+    ToggleFreeFly,
+    ToggleChase,
+    LockRoll,
+    ToggleProjection,
+    FovDecrease,
+    FovIncrease,
+    TopDown,
+    Home,
+    CycleLayout,
+    // recorridos de cámara y capturas
+    RecordKeyframe,
+    SavePath,
+    LoadPath,
+    PlayPath,
+    ExportPath,
+    Stats,
+    Screenshot,
+}
+
+const ACTION_NAMES: [(&str, Action); 48] = [
+    ("orbit_left", Action::OrbitLeft),
+    ("orbit_right", Action::OrbitRight),
+    ("orbit_up", Action::OrbitUp),
+    ("orbit_down", Action::OrbitDown),
+    ("zoom_in", Action::ZoomIn),
+    ("zoom_out", Action::ZoomOut),
+    ("pan_left", Action::PanLeft),
+    ("pan_right", Action::PanRight),
+    ("pan_up", Action::PanUp),
+    ("pan_down", Action::PanDown),
+    ("orbit_drag", Action::OrbitDrag),
+    ("pan_drag", Action::PanDrag),
+    ("select", Action::Select),
+    ("fly_forward", Action::FlyForward),
+    ("fly_back", Action::FlyBack),
+    ("fly_left", Action::FlyLeft),
+    ("fly_right", Action::FlyRight),
+    ("fly_up", Action::FlyUp),
+    ("fly_down", Action::FlyDown),
+    ("roll_left", Action::RollLeft),
+    ("roll_right", Action::RollRight),
+    ("look_left", Action::LookLeft),
+    ("look_right", Action::LookRight),
+    ("look_up", Action::LookUp),
+    ("look_down", Action::LookDown),
+    ("next_shader", Action::NextShader),
+    ("previous_shader", Action::PreviousShader),
+    ("toggle_ship", Action::ToggleShip),
+    ("toggle_lighting_model", Action::ToggleLightingModel),
+    ("cycle_ssao", Action::CycleSsao),
+    ("ssao_debug", Action::SsaoDebug),
+    ("cycle_ambient", Action::CycleAmbient),
+    ("toggle_free_fly", Action::ToggleFreeFly),
+    ("toggle_chase", Action::ToggleChase),
+    ("lock_roll", Action::LockRoll),
+    ("toggle_projection", Action::ToggleProjection),
+    ("fov_decrease", Action::FovDecrease),
+    ("fov_increase", Action::FovIncrease),
+    ("top_down", Action::TopDown),
+    ("home", Action::Home),
+    ("cycle_layout", Action::CycleLayout),
+    ("record_keyframe", Action::RecordKeyframe),
+    ("save_path", Action::SavePath),
+    ("load_path", Action::LoadPath),
+    ("play_path", Action::PlayPath),
+    ("export_path", Action::ExportPath),
+    ("stats", Action::Stats),
+    ("screenshot", Action::Screenshot),
+];
+
+impl Action {
+    /// Nombre en el archivo de configuración; los modos de shader son `shader_1` .. `shader_10`.
+    pub fn by_name(name: &str) -> Option<Action> {
+        if let Some(number) = name.strip_prefix("shader_") {
+            return number.parse::<u8>().ok().filter(|n| (1..=10).contains(n)).map(Action::Shader);
+        }
+        ACTION_NAMES.iter().find(|(n, _)| *n == name).map(|(_, action)| *action)
+    }
+}
+
+/// Una entrada física que dispara una acción.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Binding {
+    Key(KeyboardKey),
+    Mouse(MouseButton),
+    PadButton(GamepadButton),
+    // un sentido de un eje del gamepad: el valor es cuánto se inclina hacia ese lado
+    PadAxis { axis: GamepadAxis, positive: bool },
+}
+
+const KEYS: [(&str, KeyboardKey); 68] = [
+    ("A", KeyboardKey::KEY_A), ("B", KeyboardKey::KEY_B), ("C", KeyboardKey::KEY_C), ("D", KeyboardKey::KEY_D),
+    ("E", KeyboardKey::KEY_E), ("F", KeyboardKey::KEY_F), ("G", KeyboardKey::KEY_G), ("H", KeyboardKey::KEY_H),
+    ("I", KeyboardKey::KEY_I), ("J", KeyboardKey::KEY_J), ("K", KeyboardKey::KEY_K), ("L", KeyboardKey::KEY_L),
+    ("M", KeyboardKey::KEY_M), ("N", KeyboardKey::KEY_N), ("O", KeyboardKey::KEY_O), ("P", KeyboardKey::KEY_P),
+    ("Q", KeyboardKey::KEY_Q), ("R", KeyboardKey::KEY_R), ("S", KeyboardKey::KEY_S), ("T", KeyboardKey::KEY_T),
+    ("U", KeyboardKey::KEY_U), ("V", KeyboardKey::KEY_V), ("W", KeyboardKey::KEY_W), ("X", KeyboardKey::KEY_X),
+    ("Y", KeyboardKey::KEY_Y), ("Z", KeyboardKey::KEY_Z),
+    ("0", KeyboardKey::KEY_ZERO), ("1", KeyboardKey::KEY_ONE), ("2", KeyboardKey::KEY_TWO), ("3", KeyboardKey::KEY_THREE),
+    ("4", KeyboardKey::KEY_FOUR), ("5", KeyboardKey::KEY_FIVE), ("6", KeyboardKey::KEY_SIX), ("7", KeyboardKey::KEY_SEVEN),
+    ("8", KeyboardKey::KEY_EIGHT), ("9", KeyboardKey::KEY_NINE),
+    ("F1", KeyboardKey::KEY_F1), ("F2", KeyboardKey::KEY_F2), ("F3", KeyboardKey::KEY_F3), ("F4", KeyboardKey::KEY_F4),
+    ("F5", KeyboardKey::KEY_F5), ("F6", KeyboardKey::KEY_F6), ("F7", KeyboardKey::KEY_F7), ("F8", KeyboardKey::KEY_F8),
+    ("F9", KeyboardKey::KEY_F9), ("F10", KeyboardKey::KEY_F10), ("F11", KeyboardKey::KEY_F11), ("F12", KeyboardKey::KEY_F12),
+    ("UP", KeyboardKey::KEY_UP), ("DOWN", KeyboardKey::KEY_DOWN), ("LEFT", KeyboardKey::KEY_LEFT), ("RIGHT", KeyboardKey::KEY_RIGHT),
+    ("SPACE", KeyboardKey::KEY_SPACE), ("ENTER", KeyboardKey::KEY_ENTER), ("TAB", KeyboardKey::KEY_TAB),
+    ("BACKSPACE", KeyboardKey::KEY_BACKSPACE), ("HOME", KeyboardKey::KEY_HOME), ("END", KeyboardKey::KEY_END),
+    ("PAGE_UP", KeyboardKey::KEY_PAGE_UP), ("PAGE_DOWN", KeyboardKey::KEY_PAGE_DOWN),
+    ("LEFT_SHIFT", KeyboardKey::KEY_LEFT_SHIFT), ("RIGHT_SHIFT", KeyboardKey::KEY_RIGHT_SHIFT),
+    ("LEFT_CONTROL", KeyboardKey::KEY_LEFT_CONTROL), ("RIGHT_CONTROL", KeyboardKey::KEY_RIGHT_CONTROL),
+    ("LEFT_ALT", KeyboardKey::KEY_LEFT_ALT), ("LEFT_BRACKET", KeyboardKey::KEY_LEFT_BRACKET),
+    ("RIGHT_BRACKET", KeyboardKey::KEY_RIGHT_BRACKET), ("MINUS", KeyboardKey::KEY_MINUS),
+];
+
+const MOUSE_BUTTONS: [(&str, MouseButton); 3] = [
+    ("LEFT", MouseButton::MOUSE_BUTTON_LEFT),
+    ("RIGHT", MouseButton::MOUSE_BUTTON_RIGHT),
+    ("MIDDLE", MouseButton::MOUSE_BUTTON_MIDDLE),
+];
+
+const PAD_BUTTONS: [(&str, GamepadButton); 16] = [
+    ("DPAD_UP", GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_UP),
+    ("DPAD_RIGHT", GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_RIGHT),
+    ("DPAD_DOWN", GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_DOWN),
+    ("DPAD_LEFT", GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_LEFT),
+    ("Y", GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_UP),
+    ("B", GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT),
+    ("A", GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN),
+    ("X", GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_LEFT),
+    ("LB", GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_1),
+    ("LT", GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_2),
+    ("RB", GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_1),
+    ("RT", GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_2),
+    ("BACK", GamepadButton::GAMEPAD_BUTTON_MIDDLE_LEFT),
+    ("START", GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT),
+    ("LEFT_THUMB", GamepadButton::GAMEPAD_BUTTON_LEFT_THUMB),
+    ("RIGHT_THUMB", GamepadButton::GAMEPAD_BUTTON_RIGHT_THUMB),
+];
+
+const PAD_AXES: [(&str, GamepadAxis); 6] = [
+    ("LEFT_X", GamepadAxis::GAMEPAD_AXIS_LEFT_X),
+    ("LEFT_Y", GamepadAxis::GAMEPAD_AXIS_LEFT_Y),
+    ("RIGHT_X", GamepadAxis::GAMEPAD_AXIS_RIGHT_X),
+    ("RIGHT_Y", GamepadAxis::GAMEPAD_AXIS_RIGHT_Y),
+    ("LEFT_TRIGGER", GamepadAxis::GAMEPAD_AXIS_LEFT_TRIGGER),
+    ("RIGHT_TRIGGER", GamepadAxis::GAMEPAD_AXIS_RIGHT_TRIGGER),
+];
+
+fn lookup<T: Copy>(table: &[(&str, T)], name: &str) -> Option<T> {
+    table.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, value)| *value)
+}
+
+impl Binding {
+    /// `key:W`, `mouse:LEFT`, `pad:A` o `axis:LEFT_X+` / `axis:LEFT_X-`.
+    pub fn parse(text: &str) -> Result<Binding, String> {
+        let (device, name) = text.split_once(':').ok_or_else(|| format!("'{}' no tiene la forma <dispositivo>:<nombre>", text))?;
+        let unknown = || format!("entrada desconocida '{}'", text);
+        match device {
+            "key" => lookup(&KEYS, name).map(Binding::Key).ok_or_else(unknown),
+            "mouse" => lookup(&MOUSE_BUTTONS, name).map(Binding::Mouse).ok_or_else(unknown),
+            "pad" => lookup(&PAD_BUTTONS, name).map(Binding::PadButton).ok_or_else(unknown),
+            "axis" => {
+                let (axis, positive) = match name.strip_suffix('+') {
+                    Some(axis) => (axis, true),
+                    None => (name.strip_suffix('-').ok_or_else(|| format!("al eje '{}' le falta el sentido (+ o -)", text))?, false),
+                };
+                lookup(&PAD_AXES, axis).map(|axis| Binding::PadAxis { axis, positive }).ok_or_else(unknown)
+            }
+            _ => Err(format!("dispositivo desconocido '{}'", device)),
+        }
+    }
+}
+
+/// Qué entradas disparan cada acción.
+#[derive(Clone, Debug)]
+pub struct InputMap {
+    pub bindings: HashMap<Action, Vec<Binding>>,
+    pub gamepad: i32,     // índice del gamepad que se lee
+    pub deadzone: f32,    // inclinación mínima de un eje para contar
+}
+
+impl Default for InputMap {
+    /// Los controles de siempre, más un gamepad: stick izquierdo desplaza (o mueve en vuelo
+    /// libre), stick derecho gira, gatillos acercan y alejan.
+    fn default() -> Self {
+        use KeyboardKey::*;
+        let key = Binding::Key;
+        let axis = |axis, positive| Binding::PadAxis { axis, positive };
+        let pad = Binding::PadButton;
+        let (left_x, left_y) = (GamepadAxis::GAMEPAD_AXIS_LEFT_X, GamepadAxis::GAMEPAD_AXIS_LEFT_Y);
+        let (right_x, right_y) = (GamepadAxis::GAMEPAD_AXIS_RIGHT_X, GamepadAxis::GAMEPAD_AXIS_RIGHT_Y);
+
+        let mut bindings = HashMap::from([
+            (Action::OrbitLeft, vec![key(KEY_A), axis(right_x, false)]),
+            (Action::OrbitRight, vec![key(KEY_D), axis(right_x, true)]),
+            (Action::OrbitUp, vec![key(KEY_W), axis(right_y, false)]),
+            (Action::OrbitDown, vec![key(KEY_S), axis(right_y, true)]),
+            (Action::ZoomIn, vec![key(KEY_UP), axis(GamepadAxis::GAMEPAD_AXIS_RIGHT_TRIGGER, true)]),
+            (Action::ZoomOut, vec![key(KEY_DOWN), axis(GamepadAxis::GAMEPAD_AXIS_LEFT_TRIGGER, true)]),
+            (Action::PanLeft, vec![key(KEY_Q), key(KEY_LEFT), axis(left_x, false)]),
+            (Action::PanRight, vec![key(KEY_E), key(KEY_RIGHT), axis(left_x, true)]),
+            (Action::PanUp, vec![key(KEY_R), axis(left_y, false)]),
+            (Action::PanDown, vec![key(KEY_F), axis(left_y, true)]),
+            (Action::OrbitDrag, vec![Binding::Mouse(MouseButton::MOUSE_BUTTON_LEFT)]),
+            (Action::PanDrag, vec![Binding::Mouse(MouseButton::MOUSE_BUTTON_RIGHT), Binding::Mouse(MouseButton::MOUSE_BUTTON_MIDDLE)]),
+            (Action::Select, vec![Binding::Mouse(MouseButton::MOUSE_BUTTON_LEFT)]),
+            (Action::FlyForward, vec![key(KEY_W), axis(left_y, false)]),
+            (Action::FlyBack, vec![key(KEY_S), axis(left_y, true)]),
+            (Action::FlyLeft, vec![key(KEY_A), axis(left_x, false)]),
+            (Action::FlyRight, vec![key(KEY_D), axis(left_x, true)]),
+            (Action::FlyUp, vec![key(KEY_SPACE), pad(GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_1)]),
+            (Action::FlyDown, vec![key(KEY_LEFT_CONTROL), pad(GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_1)]),
+            (Action::RollLeft, vec![key(KEY_Q), pad(GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_LEFT)]),
+            (Action::RollRight, vec![key(KEY_E), pad(GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_RIGHT)]),
+            (Action::LookLeft, vec![axis(right_x, false)]),
+            (Action::LookRight, vec![axis(right_x, true)]),
+            (Action::LookUp, vec![axis(right_y, false)]),
+            (Action::LookDown, vec![axis(right_y, true)]),
+            (Action::NextShader, vec![pad(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT)]),
+            (Action::PreviousShader, vec![pad(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_LEFT)]),
+            (Action::ToggleShip, vec![key(KEY_M)]),
+            (Action::ToggleLightingModel, vec![key(KEY_P)]),
+            (Action::CycleSsao, vec![key(KEY_O)]),
+            (Action::SsaoDebug, vec![key(KEY_I)]),
+            (Action::CycleAmbient, vec![key(KEY_H)]),
+            (Action::ToggleFreeFly, vec![key(KEY_C), pad(GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT)]),
+            (Action::ToggleChase, vec![key(KEY_G), pad(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_UP)]),
+            (Action::LockRoll, vec![key(KEY_L)]),
+            (Action::ToggleProjection, vec![key(KEY_V)]),
+            (Action::FovDecrease, vec![key(KEY_LEFT_BRACKET)]),
+            (Action::FovIncrease, vec![key(KEY_RIGHT_BRACKET)]),
+            (Action::TopDown, vec![key(KEY_T)]),
+            (Action::Home, vec![key(KEY_HOME), pad(GamepadButton::GAMEPAD_BUTTON_MIDDLE_LEFT)]),
+            (Action::CycleLayout, vec![key(KEY_B)]),
+            (Action::RecordKeyframe, vec![key(KEY_F5)]),
+            (Action::SavePath, vec![key(KEY_F6)]),
+            (Action::LoadPath, vec![key(KEY_F7)]),
+            (Action::PlayPath, vec![key(KEY_F8)]),
+            (Action::ExportPath, vec![key(KEY_F9)]),
+            (Action::Stats, vec![key(KEY_F3)]),
+            (Action::Screenshot, vec![key(KEY_F12)]),
+        ]);
+
+        let digits = [KEY_ONE, KEY_TWO, KEY_THREE, KEY_FOUR, KEY_FIVE, KEY_SIX, KEY_SEVEN, KEY_EIGHT, KEY_NINE, KEY_ZERO];
+        for (index, digit) in digits.into_iter().enumerate() {
+            bindings.insert(Action::Shader(index as u8 + 1), vec![key(digit)]);
+        }
+
+        InputMap { bindings, gamepad: 0, deadzone: 0.2 }
+    }
+}
+
+impl InputMap {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        InputMap::parse(&text).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    /// Parte de los controles por defecto y reemplaza los de cada acción que aparece.
+    ///
+    /// ```text
+    /// # <acción> = <entrada>, <entrada>, ...
+    /// orbit_left = key:A, axis:RIGHT_X-
+    /// zoom_in = key:UP, axis:RIGHT_TRIGGER+
+    /// shader_4 = key:4, pad:Y
+    /// gamepad = 0
+    /// deadzone = 0.25
+    /// ```
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut map = InputMap::default();
+
+        for (number, raw) in text.lines().enumerate() {
+            let line = raw.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let at_line = |e: String| format!("línea {}: {}", number + 1, e);
+            let (name, value) = line.split_once('=').ok_or_else(|| at_line(format!("falta '=' en '{}'", line)))?;
+            let (name, value) = (name.trim(), value.trim());
+
+            match name {
+                "gamepad" => map.gamepad = value.parse().map_err(|_| at_line(format!("'{}' no es un número", value)))?,
+                "deadzone" => map.deadzone = value.parse().map_err(|_| at_line(format!("'{}' no es un número", value)))?,
+                _ => {
+                    let action = Action::by_name(name).ok_or_else(|| at_line(format!("acción desconocida '{}'", name)))?;
+                    let bindings = value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|text| !text.is_empty())
+                        .map(Binding::parse)
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(at_line)?;
+                    map.bindings.insert(action, bindings);
+                }
+            }
+        }
+
+        Ok(map)
+    }
+}
+
+/// Estado de las acciones en este cuadro. Se actualiza una vez por cuadro con `update`,
+/// y guarda el anterior para saber qué se acaba de pulsar o soltar.
+pub struct Input {
+    pub map: InputMap,
+    values: HashMap<Action, f32>,
+    previous: HashMap<Action, f32>,
+    pub mouse_position: Vector2,
+    pub mouse_delta: Vector2,
+    pub mouse_wheel: f32,
+}
+
+impl Input {
+    pub fn new(map: InputMap) -> Self {
+        Input {
+            map,
+            values: HashMap::new(),
+            previous: HashMap::new(),
+            mouse_position: Vector2::zero(),
+            mouse_delta: Vector2::zero(),
+            mouse_wheel: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, window: &RaylibHandle) {
+        let gamepad = self.map.gamepad;
+        let has_gamepad = window.is_gamepad_available(gamepad);
+        let deadzone = self.map.deadzone.clamp(0.0, 0.99);
+
+        let read = |binding: &Binding| -> f32 {
+            match *binding {
+                Binding::Key(key) => if window.is_key_down(key) { 1.0 } else { 0.0 },
+                Binding::Mouse(button) => if window.is_mouse_button_down(button) { 1.0 } else { 0.0 },
+                Binding::PadButton(button) => {
+                    if has_gamepad && window.is_gamepad_button_down(gamepad, button) { 1.0 } else { 0.0 }
+                }
+                Binding::PadAxis { axis, positive } => {
+                    if !has_gamepad {
+                        return 0.0;
+                    }
+                    let movement = window.get_gamepad_axis_movement(gamepad, axis);
+                    let amount = if positive { movement } else { -movement };
+                    // fuera de la zona muerta se reescala para que empiece en 0
+                    ((amount - deadzone) / (1.0 - deadzone)).clamp(0.0, 1.0)
+                }
+            }
+        };
+
+        let values: HashMap<Action, f32> = self
+            .map
+            .bindings
+            .iter()
+            .map(|(action, bindings)| (*action, bindings.iter().map(read).fold(0.0, f32::max)))
+            .collect();
+        self.previous = std::mem::replace(&mut self.values, values);
+
+        self.mouse_position = window.get_mouse_position();
+        self.mouse_delta = window.get_mouse_delta();
+        self.mouse_wheel = window.get_mouse_wheel_move();
+    }
+
+    /// Cuánto está activa la acción, en [0, 1]: 1 para teclas y botones, proporcional para ejes.
+    pub fn value(&self, action: Action) -> f32 {
+        self.values.get(&action).copied().unwrap_or(0.0)
+    }
+
+    pub fn is_down(&self, action: Action) -> bool {
+        self.value(action) > 0.5
+    }
+
+    pub fn is_pressed(&self, action: Action) -> bool {
+        self.is_down(action) && self.previous.get(&action).copied().unwrap_or(0.0) <= 0.5
+    }
+
+    pub fn is_released(&self, action: Action) -> bool {
+        !self.is_down(action) && self.previous.get(&action).copied().unwrap_or(0.0) > 0.5
+    }
+}
@@ -1,23 +1,165 @@
 use raylib::prelude::*;
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LightKind {
+    // emite en todas direcciones desde `position`
+    Point,
+    // rayos paralelos en `direction` (un sol muy lejano); ignora `position`
+    Directional,
+    // cono desde `position` hacia `direction`; ángulos en radianes medidos desde el eje
+    Spot { inner_angle: f32, outer_angle: f32 },
+}
+
+/// Cómo cae la intensidad con la distancia en espacio mundo. Las dos variantes con
+/// distancia llegan suavemente a cero en `range`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Attenuation {
+    None,
+    // 1/d², físicamente correcta
+    InverseSquare,
+    // 1 / (constant + linear·d + quadratic·d²), la forma clásica de OpenGL
+    Polynomial { constant: f32, linear: f32, quadratic: f32 },
+}
+
+#[derive(Clone, Copy, Debug)]
 pub struct Light {
-    pub position: Vector3,   
-    pub color: Vector3,      
-    pub intensity: f32,      
-    pub range: f32,          
+    pub kind: LightKind,
+    pub position: Vector3,
+    pub direction: Vector3,  // hacia dónde apunta la luz (direccional y foco)
+    pub color: Vector3,
+    pub intensity: f32,      // para luces con atenuación, intensidad a una unidad de distancia
+    pub range: f32,          // distancia en espacio mundo a partir de la cual no ilumina
+    pub attenuation: Attenuation,
+    pub cast_shadows: bool,
+    // tamaño de la fuente para la penumbra de los eclipses: radio en unidades de mundo,
+    // o radio angular en radianes para las direccionales (0 = puntual, sombra dura)
+    pub radius: f32,
 }
 
 impl Light {
     pub fn new(position: Vector3) -> Self {
         Light {
+            kind: LightKind::Point,
             position,
+            direction: Vector3::new(0.0, -1.0, 0.0),
             color: Vector3::new(1.0, 0.9, 0.8),
             intensity: 1.0,
             range: 200.0,
+            attenuation: Attenuation::InverseSquare,
+            cast_shadows: true,
+            radius: 0.0,
         }
     }
 
     pub fn new_with_params(position: Vector3, color: Vector3, intensity: f32, range: f32) -> Self {
-        Light { position, color, intensity, range }
+        Light { color, intensity, range, ..Light::new(position) }
+    }
+
+    pub fn directional(direction: Vector3, color: Vector3, intensity: f32) -> Self {
+        Light {
+            kind: LightKind::Directional,
+            direction: direction.normalized(),
+            color,
+            intensity,
+            attenuation: Attenuation::None,
+            ..Light::new(Vector3::zero())
+        }
+    }
+
+    pub fn spot(position: Vector3, direction: Vector3, color: Vector3, intensity: f32, inner_angle: f32, outer_angle: f32) -> Self {
+        Light {
+            kind: LightKind::Spot { inner_angle, outer_angle },
+            direction: direction.normalized(),
+            color,
+            intensity,
+            ..Light::new(position)
+        }
+    }
+
+    /// Factor de atenuación a `distance` unidades de la luz.
+    pub fn attenuation_at(&self, distance: f32) -> f32 {
+        if self.kind == LightKind::Directional {
+            return 1.0;
+        }
+
+        // ventana suave: evita el corte brusco justo en el rango
+        let ratio = (distance / self.range.max(1e-4)).powi(4);
+        let window = (1.0 - ratio).clamp(0.0, 1.0).powi(2);
+
+        match self.attenuation {
+            Attenuation::None => 1.0,
+            Attenuation::InverseSquare => window / (distance * distance).max(0.01),
+            Attenuation::Polynomial { constant, linear, quadratic } => {
+                window / (constant + linear * distance + quadratic * distance * distance).max(1e-4)
+            }
+        }
+    }
+
+    /// Luz que llega a `point` (espacio mundo): la dirección desde el punto hacia la luz
+    /// y el color ya multiplicado por la intensidad, la atenuación y el cono del foco.
+    pub fn incident(&self, point: Vector3) -> (Vector3, Vector3) {
+        match self.kind {
+            LightKind::Directional => (-self.direction, self.color * self.intensity),
+            LightKind::Point => {
+                let offset = self.position - point;
+                let falloff = self.attenuation_at(offset.length());
+                (offset.normalized(), self.color * (self.intensity * falloff))
+            }
+            LightKind::Spot { inner_angle, outer_angle } => {
+                let offset = self.position - point;
+                let to_light = offset.normalized();
+                let cos_angle = (-to_light).dot(self.direction);
+                let (cos_inner, cos_outer) = (inner_angle.cos(), outer_angle.cos());
+                // borde suave entre el ángulo interior y el exterior
+                let cone = ((cos_angle - cos_outer) / (cos_inner - cos_outer).max(1e-4)).clamp(0.0, 1.0);
+                let falloff = self.attenuation_at(offset.length());
+                (to_light, self.color * (self.intensity * cone * cone * falloff))
+            }
+        }
+    }
+
+    /// Radio angular (en radianes) del disco de la luz visto desde `point`.
+    pub fn angular_radius(&self, point: Vector3) -> f32 {
+        match self.kind {
+            LightKind::Directional => self.radius,
+            _ => {
+                let distance = (self.position - point).length();
+                (self.radius / distance.max(self.radius).max(1e-6)).asin()
+            }
+        }
+    }
+
+    /// Las luces direccionales no tienen una posición que se pueda proyectar en pantalla.
+    pub fn has_position(&self) -> bool {
+        self.kind != LightKind::Directional
+    }
+}
+
+/// Un objeto emisivo que además es una luz de la escena: la luz `light` (índice en la lista
+/// de luces) se mueve con la matriz de modelo del objeto y toma el color y la intensidad de
+/// su emisión. El objeto no debería proyectar sombra de su propia luz, porque la tapa.
+#[derive(Clone, Copy, Debug)]
+pub struct Emitter {
+    pub light: usize,
+    pub model_matrix: Matrix,
+    pub emission: Vector3,  // color por intensidad (a una unidad de distancia); puede pasar de 1
+    pub radius: f32,        // radio del objeto en mundo: el tamaño de la luz para los eclipses
+}
+
+impl Emitter {
+    pub fn apply(&self, lights: &mut [Light]) {
+        let Some(light) = lights.get_mut(self.light) else {
+            return;
+        };
+        light.position = Vector3::new(self.model_matrix.m12, self.model_matrix.m13, self.model_matrix.m14);
+        light.radius = self.radius;
+
+        let peak = self.emission.x.max(self.emission.y).max(self.emission.z);
+        if peak > 0.0 {
+            light.color = self.emission / peak;
+            light.intensity = peak;
+        } else {
+            light.intensity = 0.0;
+        }
     }
 }
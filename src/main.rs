@@ -10,44 +10,238 @@ mod vertex;
 mod light;
 mod shaders;
 mod camera;
+mod star;
+mod clouds;
+mod planet;
+mod material;
+mod normal_map;
+mod toon;
+mod surface;
+mod shadow;
+mod ssao;
+mod ambient;
+mod eclipse;
+mod timing;
+mod camera_path;
+mod bounds;
+mod input;
+mod viewport;
 
 use obj::Obj;
 use triangle::triangle;
 use framebuffer::Framebuffer;
 use raylib::prelude::*;
-use std::thread;
-use std::time::Duration;
 use std::f32::consts::PI;
-use matrix::{create_model_matrix,create_projection_matrix,create_viewport_matrix,multiply_matrix_vector4};
-use light::Light;
+use matrix::{create_model_matrix,create_mvp_matrix,multiply_matrices,multiply_matrix_vector4};
+use light::{Emitter,Light};
 use vertex::Vertex;
-use shaders::{fragment_shader1,fragment_shader2,fragment_shader3,vertex_shader,vertex_shader2,vertex_shader3,ultra_mega_vertex_shader,ultra_mega_fragment_shader};
-use camera::Camera;
+use shaders::{VertexShader,project_world_to_screen,fragment_shader1,fragment_shader2,fragment_shader3,transform_vertex,vertex_shader,vertex_shader3,ultra_mega_vertex_shader};
+use camera::{Camera,CameraMode,CameraPose,Easing,ProjectionKind};
+use star::{StarParams,star_fragment_shader,draw_corona};
+use clouds::{CloudParams,cloud_fragment_shader};
+use planet::{NightParams,earth_fragment_shader,lava_fragment_shader,moon_fragment_shader};
+use material::{Material,shader_uses_lights};
+use normal_map::NormalMap;
+use toon::{ToonParams,toon_fragment_shader,draw_outlines};
+use surface::{LightingModel,SurfaceMaterial,lit_fragment_shader};
+use shadow::{ShadowCaster,ShadowMap,ShadowSettings};
+use ssao::{AmbientOcclusion,SsaoQuality};
+use ambient::{AmbientParams,Environment,draw_environment};
+use eclipse::{Occluder,eclipse_visibility};
+use timing::FixedTimestep;
+use camera_path::{CameraPath,PathPlayer};
+use bounds::{BoundingVolume,Frustum};
+use input::{Action,Input,InputMap};
+use viewport::{Viewport,ViewportLayout};
+use std::rc::Rc;
+use std::time::{SystemTime,UNIX_EPOCH};
 
 use crate::{fragment::Fragment, matrix::create_view_matrix};
 
 
+#[derive(Clone)]
 pub struct Uniforms{
     pub model_matrix: Matrix,
     pub view_matrix: Matrix,
     pub projection_matrix: Matrix,
     pub viewport_matrix: Matrix,
-    pub time:f32
+    pub mvp_matrix: Matrix,
+    pub time:f32,
+    pub star: StarParams,
+    pub clouds: CloudParams,
+    pub night: NightParams,
+    pub normal_map: Option<Rc<NormalMap>>,
+    pub toon: ToonParams,
+    pub lights: Rc<Vec<Light>>,
+    pub eye: Vector3,
+    pub surface: SurfaceMaterial,
+    // un mapa por luz, en el mismo orden que `lights` (None si la luz no proyecta sombra)
+    pub shadow_maps: Rc<Vec<Option<ShadowMap>>>,
+    // oclusión ambiental del cuadro en espacio de pantalla (None si el SSAO está apagado)
+    pub ambient_occlusion: Option<Rc<AmbientOcclusion>>,
+    pub ambient: AmbientParams,
+    // esferas que eclipsan la luz a los demás cuerpos (sombras analíticas)
+    pub occluders: Rc<Vec<Occluder>>,
 }
 
+impl Uniforms {
+    /// Copia de estos uniforms para otro objeto: cambia la matriz de modelo y recalcula la MVP.
+    pub fn with_model_matrix(&self, model_matrix: Matrix) -> Uniforms {
+        Uniforms {
+            model_matrix,
+            mvp_matrix: create_mvp_matrix(&model_matrix, &self.view_matrix, &self.projection_matrix),
+            ..self.clone()
+        }
+    }
 
-fn render(framebuffer: &mut Framebuffer, 
-    uniforms: &Uniforms, 
-    vertex_array: &[Vertex], 
-    light: &Light,
-    vertex_shader: &dyn Fn(&Vertex, &Uniforms) -> Vertex,  
-    fragment_shader: fn(&Fragment, &Uniforms, &Light) -> Vector3,
-    ) {
-    // Vertex Shader Stage
+    /// Fracción de la luz `index` que llega a `point` según su mapa de sombras y los
+    /// eclipses de las esferas en `occluders`.
+    pub fn shadow_visibility(&self, index: usize, point: Vector3, normal: Vector3) -> f32 {
+        let mapped = match self.shadow_maps.get(index) {
+            Some(Some(shadow_map)) => shadow_map.visibility(point, normal),
+            _ => 1.0,
+        };
+        match self.lights.get(index) {
+            Some(light) if mapped > 0.0 && !self.occluders.is_empty() => mapped * eclipse_visibility(light, point, &self.occluders),
+            _ => mapped,
+        }
+    }
+
+    /// Dirección hacia cada luz y la luz que llega a `point` (espacio mundo), ya con sombras.
+    pub fn incident_lights(&self, point: Vector3, normal: Vector3) -> impl Iterator<Item = (Vector3, Vector3)> + '_ {
+        self.lights.iter().enumerate().map(move |(index, light)| {
+            let (light_dir, radiance) = light.incident(point);
+            (light_dir, radiance * self.shadow_visibility(index, point, normal))
+        })
+    }
+
+    /// Factor del SSAO para la luz ambiente en un píxel (1 = sin oclusión).
+    pub fn ambient_occlusion_at(&self, screen: Vector2) -> f32 {
+        match &self.ambient_occlusion {
+            Some(ambient_occlusion) => ambient_occlusion.at(screen.x as i32, screen.y as i32),
+            None => 1.0,
+        }
+    }
+}
+
+/// Un objeto opaco de la escena en este cuadro.
+struct SceneObject<'a> {
+    model_matrix: Matrix,
+    vertices: &'a [Vertex],
+    vertex_shader: VertexShader,
+    fragment_shader: &'a dyn Fn(&Fragment, &Uniforms) -> Vector3,
+    // los objetos emisivos no proyectan sombra: su propia luz está adentro
+    emissive: bool,
+    // las esferas (planetas y lunas) se eclipsan entre sí de forma analítica, sin shadow map
+    occluder: Option<Occluder>,
+    // volumen de la malla en espacio objeto; None si el vertex shader la deforma y no se
+    // puede saber sin transformarla (esos objetos nunca se descartan)
+    bounds: Option<BoundingVolume>,
+}
+
+/// Lo que se dibuja con un modo: sus objetos y los uniforms con sus luces y sombras.
+struct Scene<'a> {
+    mode: u8,
+    objects: Vec<SceneObject<'a>>,
+    uniforms: Uniforms,
+}
+
+/// Una vista de la escena en este cuadro: dónde se dibuja, desde qué cámara y con qué modo
+/// de shader para el objeto principal.
+struct View {
+    viewport: Viewport,
+    view_matrix: Matrix,
+    projection_matrix: Matrix,
+    eye: Vector3,
+    mode: u8,
+}
+
+impl View {
+    fn new(viewport: Viewport, camera: &Camera, mode: u8) -> Self {
+        View {
+            viewport,
+            view_matrix: camera.get_view_matrix(),
+            projection_matrix: camera.get_projection_matrix(viewport.aspect()),
+            eye: camera.eye,
+            mode,
+        }
+    }
+
+    /// Los uniforms de la escena vistos desde esta cámara y llevados a este rectángulo.
+    fn uniforms(&self, scene: &Uniforms) -> Uniforms {
+        Uniforms {
+            view_matrix: self.view_matrix,
+            projection_matrix: self.projection_matrix,
+            viewport_matrix: self.viewport.matrix(),
+            mvp_matrix: create_mvp_matrix(&scene.model_matrix, &self.view_matrix, &self.projection_matrix),
+            eye: self.eye,
+            ..scene.clone()
+        }
+    }
+}
+
+/// La esfera (planeta o luna) bajo el punto `click` de la pantalla; si hay varias, la más
+/// cercana a la cámara.
+fn pick_sphere(objects: &[SceneObject], uniforms: &Uniforms, click: Vector2) -> Option<Occluder> {
+    let view = &uniforms.view_matrix;
+    let right = Vector3::new(view.m0, view.m4, view.m8);
+    let forward = -Vector3::new(view.m2, view.m6, view.m10);
+
+    objects
+        .iter()
+        .filter_map(|object| object.occluder)
+        .filter(|sphere| (sphere.center - uniforms.eye).dot(forward) > sphere.radius)
+        .filter(|sphere| {
+            let center = project_world_to_screen(sphere.center, uniforms);
+            let edge = project_world_to_screen(sphere.center + right * sphere.radius, uniforms);
+            (click - center).length() <= (edge - center).length()
+        })
+        .min_by(|a, b| {
+            let (da, db) = ((a.center - uniforms.eye).length(), (b.center - uniforms.eye).length());
+            da.total_cmp(&db)
+        })
+}
+
+/// Si la escena de un modo tiene algún shader que lee las luces (y sus sombras), según
+/// el shader del objeto principal. La estrella (4) no ilumina, pero sí el planeta que la
+/// orbita; los modos 6 a 10 son planetas, luna, toon y el material del modelo.
+fn mode_uses_lights(mode: u8, ultra_mega_material: &Material) -> bool {
+    match mode {
+        1 => shader_uses_lights("fragment_shader1"),
+        2 => shader_uses_lights("fragment_shader2"),
+        3 => shader_uses_lights("fragment_shader3"),
+        5 => ultra_mega_material.uses_lights(),
+        _ => true,
+    }
+}
+
+/// Shaders del objeto principal según el modo (teclas 1 a 0).
+fn top_shaders(mode: u8, ultra_mega_material: &Material) -> (VertexShader, Box<dyn Fn(&Fragment, &Uniforms) -> Vector3 + '_>) {
+    match mode {
+        1 => (vertex_shader, Box::new(fragment_shader1)),
+        2 => (vertex_shader, Box::new(fragment_shader2)),
+        3 => (vertex_shader3, Box::new(fragment_shader3)),
+        4 => (vertex_shader, Box::new(star_fragment_shader)),
+        5 => (ultra_mega_vertex_shader, Box::new(|f: &Fragment, u: &Uniforms| ultra_mega_material.evaluate(f, u))),
+        6 => (vertex_shader, Box::new(earth_fragment_shader)),
+        7 => (vertex_shader, Box::new(lava_fragment_shader)),
+        8 => (vertex_shader, Box::new(moon_fragment_shader)),
+        9 => (vertex_shader, Box::new(toon_fragment_shader)),
+        10 => (vertex_shader, Box::new(lit_fragment_shader)),
+        _ => (vertex_shader, Box::new(fragment_shader1)),
+    }
+}
+
+// Etapas de vértices, ensamblado y rasterización, comunes a los pases opacos y translúcidos
+fn rasterize(uniforms: &Uniforms,
+    vertex_array: &[Vertex],
+    vertex_shader: &dyn Fn(&Vertex, &Uniforms) -> (Vector3, Vector3),
+    ) -> Vec<Fragment> {
+    // Vertex Shader Stage: el shader solo modifica posición/normal, la transformación es común
     let mut transformed_vertices = Vec::with_capacity(vertex_array.len());
     for vertex in vertex_array {
-        let transformed = vertex_shader(vertex, uniforms);
-        transformed_vertices.push(transformed);
+        let (position, normal) = vertex_shader(vertex, uniforms);
+        transformed_vertices.push(transform_vertex(vertex, position, normal, uniforms));
     }
 
     // Primitive Assembly Stage
@@ -65,17 +259,76 @@ fn render(framebuffer: &mut Framebuffer,
     // Rasterization Stage
     let mut fragments = Vec::new();
     for tri in &triangles {
-        fragments.extend(triangle(&tri[0], &tri[1], &tri[2], light));
+        fragments.extend(triangle(&tri[0], &tri[1], &tri[2]));
     }
+    fragments
+}
+
+fn render(framebuffer: &mut Framebuffer, 
+    uniforms: &Uniforms, 
+    vertex_array: &[Vertex], 
+    vertex_shader: &dyn Fn(&Vertex, &Uniforms) -> (Vector3, Vector3),
+    fragment_shader: &dyn Fn(&Fragment, &Uniforms) -> Vector3,
+    ) {
+    let fragments = rasterize(uniforms, vertex_array, vertex_shader);
 
     // Fragment Processing Stage
     for fragment in fragments {
-    let final_color = fragment_shader(&fragment, uniforms, light);          
-        framebuffer.point(
+        // scissor test antes de sombrear: lo que cae fuera de la vista no se calcula
+        if !framebuffer.in_scissor(fragment.position.x as i32, fragment.position.y as i32) {
+            continue;
+        }
+    let final_color = fragment_shader(&fragment, uniforms);          
+        // la normal queda guardada para los post pass (contornos)
+        framebuffer.point_with_normal(
             fragment.position.x as i32,
             fragment.position.y as i32,
             fragment.depth,
             final_color,
+            fragment.normal.normalized(),
+        );
+    }
+}
+
+// Solo profundidad y normales, sin sombrear: el pre-pase que necesita el SSAO
+fn render_depth(framebuffer: &mut Framebuffer,
+    uniforms: &Uniforms,
+    vertex_array: &[Vertex],
+    vertex_shader: &dyn Fn(&Vertex, &Uniforms) -> (Vector3, Vector3),
+    ) {
+    for fragment in rasterize(uniforms, vertex_array, vertex_shader) {
+        framebuffer.point_with_normal(
+            fragment.position.x as i32,
+            fragment.position.y as i32,
+            fragment.depth,
+            Vector3::zero(),
+            fragment.normal.normalized(),
+        );
+    }
+}
+
+// Igual que render, pero el fragment shader devuelve alpha en w y el resultado se mezcla
+// sobre el framebuffer sin escribir profundidad. Se dibuja después de los objetos opacos.
+fn render_translucent(framebuffer: &mut Framebuffer,
+    uniforms: &Uniforms,
+    vertex_array: &[Vertex],
+    vertex_shader: &dyn Fn(&Vertex, &Uniforms) -> (Vector3, Vector3),
+    fragment_shader: fn(&Fragment, &Uniforms) -> Vector4,
+    ) {
+    let fragments = rasterize(uniforms, vertex_array, vertex_shader);
+
+    for fragment in fragments {
+        // scissor test antes de sombrear: lo que cae fuera de la vista no se calcula
+        if !framebuffer.in_scissor(fragment.position.x as i32, fragment.position.y as i32) {
+            continue;
+        }
+        let final_color = fragment_shader(&fragment, uniforms);
+        framebuffer.blend_point(
+            fragment.position.x as i32,
+            fragment.position.y as i32,
+            fragment.depth,
+            Vector3::new(final_color.x, final_color.y, final_color.z),
+            final_color.w,
         );
     }
 }
@@ -83,7 +336,6 @@ fn render(framebuffer: &mut Framebuffer,
 fn main() {
     let window_width = 1000;
     let window_height = 720;
-    let start_time = std::time::Instant::now();
 
     let (mut window, raylib_thread) = raylib::init()
         .size(window_width, window_height)
@@ -95,10 +347,62 @@ fn main() {
     let mut translation = Vector3::new(0.0, 0.0,0.0);
     let mut scale = 1.0;
     let mut rotation = Vector3:: new(0.0, 0.0, 0.0);
-    let light = Light::new(Vector3::new(5.0, 5.0, 5.0));
+    // sol, foco de la nave y una luz de relleno lejana
+    // Índice del sol en `lights`; con la estrella en pantalla (modo 4) pasa a ser su luz
+    const SUN: usize = 0;
+    // Las intensidades son a una unidad de distancia: con 1/d² el sol queda en ~1 sobre el origen
+    let mut headlight = Light::spot(
+        Vector3::new(-3.0, 1.0, 3.0),
+        Vector3::new(3.0, -1.0, -3.0),
+        Vector3::new(1.0, 0.95, 0.85),
+        15.0,
+        12.0_f32.to_radians(),
+        22.0_f32.to_radians(),
+    );
+    headlight.range = 30.0;
+    // el sol tiene tamaño para que los eclipses tengan penumbra
+    let mut sun = Light::new_with_params(Vector3::new(5.0, 5.0, 5.0), Vector3::new(1.0, 0.9, 0.8), 75.0, 200.0);
+    sun.radius = 0.4;
+    let lights = vec![
+        sun,
+        headlight,
+        Light::directional(Vector3::new(0.6, 0.3, 0.5), Vector3::new(0.4, 0.5, 0.8), 0.15),
+    ];
+    let star_params = StarParams::default();
+    let cloud_params = CloudParams::default();
+    let night_params = NightParams::default();
+    let toon_params = ToonParams::default();
+    let shadow_settings = ShadowSettings::default();
+    // el fondo estrellado es también la fuente de la luz ambiente (armónicos esféricos)
+    let environment = Environment::default();
+    let mut ambient_params = AmbientParams::new(&environment);
+    let rock_normal_map = match NormalMap::load("models/rock_normal.png") {
+        Ok(map) => Some(Rc::new(map)),
+        Err(e) => {
+            eprintln!("Sin normal map: {}", e);
+            None
+        }
+    };
 
-    let obj = Obj::load("models/sphere.obj").expect("Error al leer archivo"); //se puede cambiar al spaceship 
-    let vertex_array = obj.get_vertex_array();
+    // La combinación del modo 5 se define como pila de capas en un archivo
+    let ultra_mega_material = Material::load("materials/ultra_mega.mat").expect("Error al leer material");
+
+    let obj = Obj::load("models/sphere.obj").expect("Error al leer archivo");
+    // radio de models/sphere.obj en espacio objeto: la distancia del origen al vértice más
+    // lejano (sale de 0.48 a 0.52), para que toda la superficie quede dentro de su esfera
+    let sphere_radius = obj.bounds.center.length() + obj.bounds.radius;
+    let sphere_vertex_array = obj.get_vertex_array();
+    let ship = Obj::load("models/improvisada.obj").expect("Error al leer archivo");
+    let ship_vertex_array = ship.get_vertex_array();
+    // M alterna entre la esfera y la nave
+    let mut show_ship = false;
+    // P alterna entre Blinn-Phong y PBR para el material del modelo
+    let mut lighting_model = LightingModel::BlinnPhong;
+    // O cambia la calidad del SSAO (apagado, bajo, medio, alto); I muestra solo la oclusión
+    let mut ssao_quality = SsaoQuality::Off;
+    let mut show_ssao_debug = false;
+    // F3 muestra las estadísticas del cuadro (objetos descartados por el frustum culling)
+    let mut show_stats = false;
     let mut camera = Camera::new(
         Vector3::new(0.0, 0.0, 5.0), // eye
         Vector3::new(0.0, 0.0, 0.0), // target
@@ -107,9 +411,35 @@ fn main() {
 
     framebuffer.set_background_color(Color::new(35,6, 48,1));
 
+    // Clic (sin arrastrar) sobre un planeta o luna: la cámara vuela hasta él. Inicio vuelve
+    // a la vista del principio.
+    let home_pose = camera.pose();
+
+    // Los controles salen de acciones con nombre; config/input.cfg cambia qué teclas,
+    // botones del ratón o del gamepad las disparan
+    let input_map = InputMap::load("config/input.cfg").unwrap_or_else(|e| {
+        eprintln!("Controles por defecto: {}", e);
+        InputMap::default()
+    });
+    let mut input = Input::new(input_map);
+    let mut click_start: Option<Vector2> = None;
+
     // estado del modo activo: 1, 2, o 3 (switch)
     let mut active_mode: u8 = 1; // default
 
+    // B cambia el reparto de la ventana: una vista, la vista con el mapa del sistema en un
+    // recuadro, o dos mitades para comparar shaders. Al entrar a la comparación, la mitad
+    // derecha se queda con el modo actual y los números cambian el de la izquierda.
+    let mut layout = ViewportLayout::Single;
+    let mut compare_mode = active_mode;
+    const MAP_HEIGHT: f32 = 8.0;
+    let mut map_camera = Camera::new(
+        translation + Vector3::new(0.0, MAP_HEIGHT, 0.0),
+        translation,
+        Vector3::new(0.0, 0.0, -1.0),
+    );
+    map_camera.projection.kind = ProjectionKind::Orthographic;
+
     let model_matrix = create_model_matrix(translation, scale, rotation);
     let model_matrix_bottom = create_model_matrix(
         Vector3::new(0.0, -2.5, 0.0),  // move down
@@ -117,74 +447,504 @@ fn main() {
         Vector3::new(PI, 0.0, 0.0),  // flip on Y axis
     );
 
+    // Reloj de las animaciones, que avanza en pasos fijos de 1/60 s
+    let mut timestep = FixedTimestep::new(1.0 / 60.0);
+    let mut simulation_time: f32 = 0.0;
+    let mut previous_simulation_time: f32 = 0.0;
+
+    // Recorridos de cámara: F5 graba la pose actual, F6 guarda, F7 carga, F8 reproduce o
+    // detiene, y F9 reproduce exportando cada cuadro a frames/ a 30 cuadros por segundo
+    const PATH_FILE: &str = "paths/flythrough.path";
+    const EXPORT_FRAME_TIME: f32 = 1.0 / 30.0;
+    let mut camera_path = CameraPath::default();
+    let mut recording_start: Option<f32> = None;
+    let mut path_player: Option<PathPlayer> = None;
+    let mut exported_frames: Option<u32> = None;
+
     while !window.window_should_close() {
-        let elapsed = start_time.elapsed().as_secs_f32();
-        camera.process_input(&window);
+        // Tiempo real del cuadro para los controles; la simulación avanza en pasos fijos y
+        // se dibuja interpolada entre los dos últimos
+        let frame_time = timestep.begin_frame();
+        input.update(&window);
+        if path_player.is_none() {
+            camera.process_input(&input, frame_time);
+        }
+        while timestep.step() {
+            previous_simulation_time = simulation_time;
+            simulation_time += timestep.step;
+            camera.fixed_update(timestep.step);
+        }
+        let alpha = timestep.alpha();
+        camera.interpolate(alpha);
+        if let Some(player) = &mut path_player {
+            player.update(&mut camera, frame_time);
+        }
+        let mut elapsed = previous_simulation_time + (simulation_time - previous_simulation_time) * alpha;
 
         // --- DETECTAR PULSACIONES (switch behavior) ---
-        // Usamos is_key_pressed para que sea una pulsación única (toggle-like).
-        if window.is_key_pressed(KeyboardKey::KEY_ONE) {
-            active_mode = 1;
-        } else if window.is_key_pressed(KeyboardKey::KEY_TWO) {
-            active_mode = 2;
-        } else if window.is_key_pressed(KeyboardKey::KEY_THREE) {
-            active_mode = 3;
-        } else if window.is_key_pressed(KeyboardKey::KEY_FOUR) {
-            active_mode = 4;
-        }
-
-        framebuffer.clear();
+        // Usamos is_pressed para que sea una pulsación única (toggle-like).
+        if let Some(mode) = (1..=10).find(|mode| input.is_pressed(Action::Shader(*mode))) {
+            active_mode = mode;
+        } else if input.is_pressed(Action::NextShader) {
+            active_mode = active_mode % 10 + 1;
+        } else if input.is_pressed(Action::PreviousShader) {
+            active_mode = (active_mode + 8) % 10 + 1;
+        }
+        if input.is_pressed(Action::ToggleShip) {
+            show_ship = !show_ship;
+        }
+        if input.is_pressed(Action::ToggleLightingModel) {
+            lighting_model = match lighting_model {
+                LightingModel::BlinnPhong => LightingModel::Pbr,
+                LightingModel::Pbr => LightingModel::BlinnPhong,
+            };
+        }
+        if input.is_pressed(Action::CycleSsao) {
+            ssao_quality = ssao_quality.next();
+        }
+        if input.is_pressed(Action::SsaoDebug) {
+            show_ssao_debug = !show_ssao_debug;
+        }
+        if input.is_pressed(Action::Stats) {
+            show_stats = !show_stats;
+        }
+        // C alterna la cámara entre órbita y vuelo libre (ratón + WASD, espacio/control, Q/E,
+        // o los sticks del gamepad)
+        if input.is_pressed(Action::ToggleFreeFly) {
+            camera.toggle_mode(&mut window);
+        }
+        if input.is_pressed(Action::Home) {
+            camera.fly_to(home_pose, 1.2, Easing::EaseInOutCubic);
+        }
+        if input.is_pressed(Action::Select) {
+            click_start = Some(input.mouse_position);
+        }
+        let click = if camera.mode == CameraMode::Orbit && input.is_released(Action::Select) {
+            click_start
+                .take()
+                .map(|start| (start, input.mouse_position))
+                .filter(|(start, end)| (*end - *start).length() < 4.0)
+                .map(|(_, end)| end)
+        } else {
+            None
+        };
+        // G alterna entre la órbita y la cámara de persecución; L fija su roll al del objeto
+        if input.is_pressed(Action::ToggleChase) {
+            let mode = if camera.mode == CameraMode::Chase { CameraMode::Orbit } else { CameraMode::Chase };
+            camera.set_mode(mode, &mut window);
+        }
+        if input.is_pressed(Action::LockRoll) {
+            camera.chase.lock_roll = !camera.chase.lock_roll;
+        }
+        // V alterna perspectiva/ortográfica, [ y ] cambian el FOV, y T pasa al mapa del
+        // sistema: ortográfica desde arriba sobre el objetivo actual
+        if input.is_pressed(Action::ToggleProjection) {
+            camera.projection.toggle();
+        }
+        if input.is_down(Action::FovDecrease) {
+            camera.adjust_fov(-1.2 * frame_time);
+        }
+        if input.is_down(Action::FovIncrease) {
+            camera.adjust_fov(1.2 * frame_time);
+        }
+        if input.is_pressed(Action::TopDown) && camera.mode == CameraMode::Orbit {
+            camera.projection.kind = ProjectionKind::Orthographic;
+            let top_down = CameraPose { pitch: PI / 2.0, ..camera.pose() };
+            camera.fly_to(top_down, 1.0, Easing::EaseInOutCubic);
+        }
+        if input.is_pressed(Action::RecordKeyframe) {
+            let start = *recording_start.get_or_insert(simulation_time);
+            camera_path.record(&camera, simulation_time - start);
+            println!("Pose {} grabada", camera_path.keyframes.len());
+        }
+        if input.is_pressed(Action::SavePath) {
+            match camera_path.save(PATH_FILE) {
+                Ok(()) => println!("Recorrido guardado en {}", PATH_FILE),
+                Err(e) => eprintln!("No se pudo guardar el recorrido: {}", e),
+            }
+        }
+        if input.is_pressed(Action::LoadPath) {
+            match CameraPath::load(PATH_FILE) {
+                Ok(path) => {
+                    camera_path = path;
+                    recording_start = None;
+                }
+                Err(e) => eprintln!("No se pudo cargar el recorrido: {}", e),
+            }
+        }
+        let play = input.is_pressed(Action::PlayPath);
+        let export = input.is_pressed(Action::ExportPath);
+        if play || export {
+            if path_player.is_some() {
+                path_player = None;
+            } else if !camera_path.keyframes.is_empty() {
+                camera.set_mode(CameraMode::Orbit, &mut window);
+                // la primera pose se aplica ya, para que este cuadro salga desde ella
+                let mut player = PathPlayer::new(camera_path.clone());
+                player.update(&mut camera, 0.0);
+                path_player = Some(player);
+                if export {
+                    if let Err(e) = std::fs::create_dir_all("frames") {
+                        eprintln!("No se pudo crear frames/: {}", e);
+                    }
+                    exported_frames = Some(0);
+                    timestep.fixed_frame_time = Some(EXPORT_FRAME_TIME);
+                    // las animaciones arrancan desde cero: dos exportaciones del mismo
+                    // recorrido dan los mismos cuadros, sin importar cuándo se pulsó F9
+                    timestep.reset();
+                    simulation_time = 0.0;
+                    previous_simulation_time = 0.0;
+                    elapsed = 0.0;
+                }
+            }
+        }
+        if input.is_pressed(Action::CycleLayout) {
+            layout = layout.next();
+            if layout == ViewportLayout::SideBySide {
+                compare_mode = active_mode;
+            }
+        }
+        // H alterna la luz ambiente: constante, hemisferio cielo/suelo o entorno (SH)
+        if input.is_pressed(Action::CycleAmbient) {
+            ambient_params.next_mode();
+        }
+        let vertex_array = if show_ship { &ship_vertex_array } else { &sphere_vertex_array };
+        let surface = SurfaceMaterial {
+            model: lighting_model,
+            ..if show_ship { ship.material } else { obj.material }
+        };
+
         framebuffer.set_current_color(Color::new(200, 200, 255, 255));
 
-        let view_matrix = camera.get_view_matrix();
-        let projection_matrix = create_projection_matrix(PI / 3.0, window_width as f32 / window_height as f32, 0.1, 100.0);
-        let viewport_matrix = create_viewport_matrix(0.0, 0.0, window_width as f32, window_height as f32);
 
         // --- ELECCION DE SHADERS PARA EL OBJETO SUPERIOR SEGUN active_mode ---
-        let (vertex_top, fragment_top): (
-            Box<dyn Fn(&Vertex, &Uniforms) -> Vertex>,
-            fn(&Fragment, &Uniforms,&Light) -> Vector3
-        ) = match active_mode {
-            1 => (Box::new(vertex_shader), fragment_shader1),
-            2 => (Box::new(vertex_shader2), fragment_shader2),
-            3 => (Box::new(vertex_shader3), fragment_shader3),
-            4 => (Box::new(ultra_mega_vertex_shader), ultra_mega_fragment_shader),
-            _ => (Box::new(vertex_shader), fragment_shader1),
-        };
+        let (vertex_top, fragment_top) = top_shaders(active_mode, &ultra_mega_material);
+        // La mitad derecha de la comparación lado a lado usa el modo que quedó fijado
+        let (vertex_compare, fragment_compare) = top_shaders(compare_mode, &ultra_mega_material);
 
         // Para la copia inferior (solo renderizamos si active_mode == 3)
         let (vertex_bottom, fragment_bottom): (
-            Box<dyn Fn(&Vertex, &Uniforms) -> Vertex>,
-            fn(&Fragment, &Uniforms,&Light) -> Vector3
-        ) = (Box::new(vertex_shader), fragment_shader1); // valores por defecto si se llegara a usar
+            VertexShader,
+            Box<dyn Fn(&Fragment, &Uniforms) -> Vector3>
+        ) = (vertex_shader, Box::new(fragment_shader1)); // valores por defecto si se llegara a usar
 
-        // uniforms para la parte superior
-        let uniforms = Uniforms {
-            model_matrix,
-            view_matrix,
-            projection_matrix,
-            viewport_matrix,
-            time:elapsed
+        // Los planetas (modos 6, 7 y 8) giran sobre su eje
+        let model_top_for = |mode: u8| {
+            if (6..=8).contains(&mode) {
+                create_model_matrix(translation, scale, Vector3::new(0.0, elapsed * 0.1, 0.0))
+            } else {
+                model_matrix
+            }
         };
 
-        // render superior (siempre)
-        render(&mut framebuffer, &uniforms, &vertex_array, &light, &vertex_top.as_ref(), fragment_top);
-
-        // Si el modo es 3, dibujamos la copia inferior (duplicado). Si quieres que la copia tenga
-        // un fragment shader distinto, cámbialo aquí (por ejemplo fragment_shader2).
-        if active_mode == 3 {
-            let uniforms_bottom = Uniforms {
-                model_matrix: model_matrix_bottom,
-                view_matrix,
-                projection_matrix,
-                viewport_matrix,
-                time:elapsed
+        // Una luna pequeña orbita los planetas de los modos 6 y 7
+        let moon_angle = elapsed * 0.35;
+        let moon_matrix = create_model_matrix(
+            translation + Vector3::new(moon_angle.cos() * 2.2, moon_angle.sin() * 0.3, moon_angle.sin() * 2.2),
+            scale * 0.27,
+            Vector3::new(0.0, moon_angle, 0.0),
+        );
+
+        // Con la estrella (modo 4) un planeta la orbita y recibe su luz
+        let orbit_angle = elapsed * 0.25;
+        let orbiting_planet_matrix = create_model_matrix(
+            translation + Vector3::new(orbit_angle.cos() * 3.2, 0.0, orbit_angle.sin() * 3.2),
+            scale * 0.35,
+            Vector3::new(0.0, elapsed * 0.4, 0.0),
+        );
+
+        // La cámara de persecución sigue a lo que se mueve: la luna, el planeta que orbita la
+        // estrella o, si no hay ninguno, el objeto principal
+        if camera.mode == CameraMode::Chase {
+            let subject = match active_mode {
+                6 | 7 => moon_matrix,
+                4 => orbiting_planet_matrix,
+                _ => model_top_for(active_mode),
+            };
+            camera.update_chase(&subject, frame_time);
+        }
+
+        // Vistas de este cuadro, en orden de dibujo. El recuadro es el mapa del sistema desde
+        // arriba; en la comparación, la mitad derecha usa la misma cámara con otro modo.
+        map_camera.target = translation;
+        map_camera.eye = translation + Vector3::new(0.0, MAP_HEIGHT, 0.0);
+        map_camera.distance = MAP_HEIGHT;
+        let views: Vec<View> = layout
+            .viewports(window_width, window_height)
+            .into_iter()
+            .enumerate()
+            .map(|(index, viewport)| match (layout, index) {
+                (ViewportLayout::PictureInPicture, 1) => View::new(viewport, &map_camera, active_mode),
+                (ViewportLayout::SideBySide, 1) => View::new(viewport, &camera, compare_mode),
+                _ => View::new(viewport, &camera, active_mode),
+            })
+            .collect();
+
+        // Los vertex shaders de los modos 3 y 5 deforman la malla fuera de su volumen
+        let top_mesh_bounds = if show_ship { ship.bounds } else { obj.bounds };
+
+        // Una escena por cada modo que se ve: la estrella trae su planeta y su luz, los
+        // planetas su luna, el modo 3 su copia inferior. Así cada mitad de la comparación
+        // muestra su modo completo.
+        let mut scenes: Vec<Scene> = Vec::new();
+        for view in &views {
+            let mode = view.mode;
+            if scenes.iter().any(|scene| scene.mode == mode) {
+                continue;
+            }
+            let (vertex_top, fragment_top) = if mode == active_mode {
+                (vertex_top, fragment_top.as_ref())
+            } else {
+                (vertex_compare, fragment_compare.as_ref())
+            };
+            let model_top = model_top_for(mode);
+            let has_moon = mode == 6 || mode == 7;
+            let has_orbiting_planet = mode == 4;
+
+            // Objetos emisivos que además iluminan: la estrella es la luz del sol
+            let top_is_emissive = mode == 4;
+            let mut emitters = Vec::new();
+            if top_is_emissive {
+                emitters.push(Emitter {
+                    light: SUN,
+                    model_matrix: model_top,
+                    emission: star_params.emission(),
+                    radius: Occluder::from_model_matrix(&model_top, sphere_radius).radius,
+                });
+            }
+            let mut frame_lights = lights.clone();
+            for emitter in &emitters {
+                emitter.apply(&mut frame_lights);
+            }
+
+            let top_deformed = matches!(mode, 3 | 5);
+            let top_bounds = (!top_deformed).then_some(top_mesh_bounds);
+
+            // Objetos opacos de este cuadro, en orden de dibujo
+            let mut objects = vec![SceneObject {
+                model_matrix: model_top,
+                vertices: vertex_array,
+                vertex_shader: vertex_top,
+                fragment_shader: fragment_top,
+                emissive: top_is_emissive,
+                // con la nave no es una esfera; la estrella es la luz, no tapa nada; y los modos 3
+                // y 5 deforman la malla, así que proyectan sombra con el shadow map, que pasa
+                // por el mismo vertex shader
+                occluder: (!show_ship && !top_is_emissive && !top_deformed)
+                    .then(|| Occluder::from_model_matrix(&model_top, sphere_radius)),
+                bounds: top_bounds,
+            }];
+            if has_orbiting_planet {
+                objects.push(SceneObject {
+                    model_matrix: orbiting_planet_matrix,
+                    vertices: &sphere_vertex_array,
+                    vertex_shader,
+                    fragment_shader: &earth_fragment_shader,
+                    emissive: false,
+                    occluder: Some(Occluder::from_model_matrix(&orbiting_planet_matrix, sphere_radius)),
+                    bounds: Some(obj.bounds),
+                });
+            }
+            if has_moon {
+                objects.push(SceneObject {
+                    model_matrix: moon_matrix,
+                    vertices: &sphere_vertex_array,
+                    vertex_shader,
+                    fragment_shader: &moon_fragment_shader,
+                    emissive: false,
+                    occluder: Some(Occluder::from_model_matrix(&moon_matrix, sphere_radius)),
+                    bounds: Some(obj.bounds),
+                });
+            }
+            // Si el modo es 3, dibujamos la copia inferior (duplicado). Si quieres que la copia tenga
+            // un fragment shader distinto, cámbialo aquí (por ejemplo fragment_shader2).
+            if mode == 3 {
+                objects.push(SceneObject {
+                    model_matrix: model_matrix_bottom,
+                    vertices: vertex_array,
+                    vertex_shader: vertex_top,
+                    fragment_shader: fragment_top,
+                    emissive: false,
+                    occluder: None,
+                    bounds: top_bounds,
+                });
+            }
+
+            // uniforms de la escena; cada vista los copia con su cámara y su rectángulo
+            let mut uniforms = Uniforms {
+                model_matrix: model_top,
+                view_matrix: view.view_matrix,
+                projection_matrix: view.projection_matrix,
+                viewport_matrix: view.viewport.matrix(),
+                mvp_matrix: create_mvp_matrix(&model_top, &view.view_matrix, &view.projection_matrix),
+                time:elapsed,
+                star: star_params,
+                clouds: cloud_params,
+                night: night_params,
+                normal_map: rock_normal_map.clone(),
+                toon: toon_params,
+                lights: Rc::new(frame_lights),
+                eye: view.eye,
+                surface,
+                shadow_maps: Rc::new(Vec::new()),
+                ambient_occlusion: None,
+                ambient: ambient_params,
+                occluders: Rc::new(objects.iter().filter_map(|object| object.occluder).collect()),
             };
-            render(&mut framebuffer, &uniforms_bottom, &vertex_array, &light, vertex_top.as_ref(), fragment_top);
+
+            // Pase de sombras: la escena completa vista desde cada luz que proyecta sombra,
+            // con los vertex shaders de cada objeto (los modos 3 y 5 deforman la malla).
+            // Los objetos emisivos no proyectan sombra: su propia luz está dentro de ellos.
+            // Las esferas tampoco: sus sombras son los eclipses analíticos de `occluders`.
+            // Solo hacen falta si algún shader del modo lee las luces.
+            let casters: Vec<ShadowCaster> = objects
+                .iter()
+                .filter(|object| !object.emissive && object.occluder.is_none())
+                .map(|object| ShadowCaster {
+                    model_matrix: object.model_matrix,
+                    vertices: object.vertices,
+                    vertex_shader: object.vertex_shader,
+                })
+                .collect();
+            let lights_sampled = mode_uses_lights(mode, &ultra_mega_material);
+            let shadow_maps: Vec<Option<ShadowMap>> = uniforms
+                .lights
+                .iter()
+                .map(|light| (light.cast_shadows && lights_sampled).then(|| ShadowMap::render(light, &casters, &uniforms, shadow_settings)).flatten())
+                .collect();
+            uniforms.shadow_maps = Rc::new(shadow_maps);
+            scenes.push(Scene { mode, objects, uniforms });
+        }
+
+        // El clic elige en la vista de más arriba bajo el cursor (el recuadro tapa a la principal)
+        if let Some(click) = click {
+            let clicked_view = views.iter().rev().find(|view| view.viewport.contains_point(click));
+            let picked = clicked_view.and_then(|view| {
+                let scene = scenes.iter().find(|scene| scene.mode == view.mode)?;
+                pick_sphere(&scene.objects, &view.uniforms(&scene.uniforms), click)
+            });
+            if let Some(sphere) = picked {
+                camera.focus(sphere.center, sphere.radius, 1.0, Easing::EaseInOutCubic);
+            }
+        }
+
+        let mut drawn_objects = 0;
+        let mut culled_objects = 0;
+        for view in &views {
+            let Some(scene) = scenes.iter().find(|scene| scene.mode == view.mode) else {
+                continue;
+            };
+            // todo lo que sigue queda recortado al rectángulo de la vista
+            framebuffer.set_scissor(view.viewport);
+            framebuffer.clear();
+            let mut uniforms = view.uniforms(&scene.uniforms);
+
+            // Frustum culling: los objetos que quedan fuera de la vista no pasan por el pipeline.
+            // Las sombras usan la lista completa, porque un objeto fuera de cuadro puede proyectarlas.
+            let frustum = Frustum::from_matrix(&multiply_matrices(&view.projection_matrix, &view.view_matrix));
+            let visible_objects: Vec<&SceneObject> = scene
+                .objects
+                .iter()
+                .filter(|object| object.bounds.is_none_or(|bounds| frustum.contains(&bounds.transformed(&object.model_matrix))))
+                .collect();
+            drawn_objects += visible_objects.len();
+            culled_objects += scene.objects.len() - visible_objects.len();
+
+            // SSAO: pre-pase de profundidad y normales de todos los objetos, y con eso la
+            // oclusión que los shaders aplican a su luz ambiente
+            if let Some(settings) = ssao_quality.settings() {
+                for object in &visible_objects {
+                    let object_uniforms = uniforms.with_model_matrix(object.model_matrix);
+                    render_depth(&mut framebuffer, &object_uniforms, object.vertices, &object.vertex_shader);
+                }
+                uniforms.ambient_occlusion = Some(Rc::new(AmbientOcclusion::compute(&framebuffer, &uniforms, &settings)));
+                framebuffer.clear();
+            }
+
+            for object in &visible_objects {
+                let object_uniforms = uniforms.with_model_matrix(object.model_matrix);
+                render(&mut framebuffer, &object_uniforms, object.vertices, &object.vertex_shader, object.fragment_shader);
+            }
+
+            // Cel shading: contornos por discontinuidad de profundidad/normales
+            if view.mode == 9 {
+                draw_outlines(&mut framebuffer, &uniforms);
+            }
+
+            // Fondo estrellado en los píxeles sin geometría
+            draw_environment(&mut framebuffer, &environment, &uniforms);
+
+            // La estrella lleva corona y prominencias alrededor (post pass sobre el fondo)
+            if view.mode == 4 {
+                let star_center = Vector3::new(model_matrix.m12, model_matrix.m13, model_matrix.m14);
+                draw_corona(&mut framebuffer, &uniforms, star_center);
+            }
+
+            // Capa de nubes: una esfera un poco más grande con su propia rotación, mezclada encima.
+            // Siempre es la esfera, aunque el modelo de arriba sea la nave
+            if view.mode == 6 {
+                let cloud_matrix = create_model_matrix(
+                    translation,
+                    scale * cloud_params.altitude,
+                    Vector3::new(0.0, cloud_params.rotation_angle(elapsed), 0.0),
+                );
+                let uniforms_clouds = uniforms.with_model_matrix(cloud_matrix);
+                render_translucent(&mut framebuffer, &uniforms_clouds, &sphere_vertex_array, &vertex_shader, cloud_fragment_shader);
+            }
+
+            // Vista de depuración del SSAO (tecla I)
+            if show_ssao_debug {
+                if let Some(ambient_occlusion) = &uniforms.ambient_occlusion {
+                    ambient_occlusion.draw_debug(&mut framebuffer);
+                }
+            }
+        }
+
+        // Marcos entre las vistas y, en la comparación, el modo de cada mitad
+        framebuffer.reset_scissor();
+        if views.len() > 1 {
+            for view in &views {
+                framebuffer.draw_border(view.viewport, Color::new(200, 200, 255, 255));
+                if layout == ViewportLayout::SideBySide {
+                    let label = format!("modo {}", view.mode);
+                    framebuffer.draw_text(&label, view.viewport.x + 10, view.viewport.y + view.viewport.height - 30, 20, Color::WHITE);
+                }
+            }
+        }
+
+        // F3: cuántos objetos se dibujaron y cuántos descartó el frustum culling
+        if show_stats {
+            let stats = format!("objetos: {} dibujados, {} descartados", drawn_objects, culled_objects);
+            framebuffer.draw_text(&stats, 10, 10, 20, Color::WHITE);
+        }
+
+        // F12 guarda el cuadro actual en screenshots/
+        if input.is_pressed(Action::Screenshot) {
+            let stamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+            match std::fs::create_dir_all("screenshots") {
+                Ok(()) => {
+                    let file = format!("screenshots/screenshot_{}.png", stamp);
+                    framebuffer.color_buffer.export_image(&file);
+                    println!("Captura guardada en {}", file);
+                }
+                Err(e) => eprintln!("No se pudo crear screenshots/: {}", e),
+            }
+        }
+
+        if let Some(count) = &mut exported_frames {
+            framebuffer.color_buffer.export_image(&format!("frames/frame_{:05}.png", count));
+            *count += 1;
+        }
+        if path_player.as_ref().is_some_and(|player| player.is_finished()) {
+            path_player = None;
+        }
+        if path_player.is_none() && exported_frames.is_some() {
+            exported_frames = None;
+            timestep.fixed_frame_time = None;
         }
 
         framebuffer.swap_buffers(&mut window, &raylib_thread);
 
-        thread::sleep(Duration::from_millis(16));
+        timestep.end_frame();
     }
 }
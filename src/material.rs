@@ -0,0 +1,294 @@
+// material.rs
+#![allow(dead_code)]
+
+use raylib::prelude::*;
+use noise::{NoiseFn, SuperSimplex};
+use crate::Uniforms;
+use crate::fragment::Fragment;
+use crate::planet::{earth_fragment_shader, lava_fragment_shader};
+use crate::shaders::{fragment_shader1, fragment_shader2, fragment_shader3, project_world_to_screen, screen_attenuation};
+use crate::star::star_fragment_shader;
+use crate::surface::{fragment_world_position, lit_fragment_shader};
+
+pub type FragmentShader = fn(&Fragment, &Uniforms) -> Vector3;
+
+/// Busca un fragment shader existente por nombre, para poder usarlo como capa desde un archivo.
+// Nombre, shader, y si lee las luces de la escena (y con eso sus sombras)
+const NAMED_SHADERS: [(&str, FragmentShader, bool); 7] = [
+    ("fragment_shader1", fragment_shader1, true),
+    ("fragment_shader2", fragment_shader2, true),
+    ("fragment_shader3", fragment_shader3, false),
+    ("star", star_fragment_shader, false),
+    ("earth", earth_fragment_shader, true),
+    ("lava", lava_fragment_shader, true),
+    ("lit", lit_fragment_shader, true),
+];
+
+pub fn fragment_shader_by_name(name: &str) -> Option<FragmentShader> {
+    NAMED_SHADERS.iter().find(|(n, _, _)| *n == name).map(|(_, shader, _)| *shader)
+}
+
+/// Si el shader con ese nombre ilumina con las luces de la escena.
+pub fn shader_uses_lights(name: &str) -> bool {
+    NAMED_SHADERS.iter().any(|(n, _, lit)| *n == name && *lit)
+}
+
+/// De dónde sale el color de una capa.
+#[derive(Clone)]
+pub enum LayerSource {
+    Color(Vector3),
+    // color base iluminado con el material del modelo (Blinn-Phong o PBR)
+    FragmentColor,
+    // uno de los fragment shaders con nombre; `lit` si usa las luces de la escena
+    Shader { shader: FragmentShader, lit: bool },
+    // ruido en espacio de pantalla animado con el tiempo, en [0, 1]
+    Noise { frequency: f32, speed: f32, generator: SuperSimplex },
+    // brillo aditivo alrededor de la luz proyectada en pantalla; radius en píxeles
+    Glow { strength: f32, radius: f32 },
+    // término N·L en escala de grises, sumado sobre todas las luces
+    Lambert,
+}
+
+impl LayerSource {
+    pub fn noise(frequency: f32, speed: f32, seed: u32) -> Self {
+        LayerSource::Noise { frequency, speed, generator: SuperSimplex::new(seed) }
+    }
+
+    pub fn evaluate(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        match self {
+            LayerSource::Color(color) => *color,
+            LayerSource::FragmentColor => lit_fragment_shader(fragment, uniforms),
+            LayerSource::Shader { shader, .. } => shader(fragment, uniforms),
+            LayerSource::Noise { frequency, speed, generator } => {
+                let x = fragment.position.x * frequency;
+                let y = fragment.position.y * frequency;
+                let t = uniforms.time * speed;
+                let raw = generator.get([x as f64, y as f64, t as f64]);
+                let value = ((raw + 1.0) * 0.5) as f32;
+                Vector3::new(value, value, value)
+            }
+            LayerSource::Glow { strength, radius } => {
+                // el brillo depende de cuánta luz llega realmente al objeto
+                let position = fragment_world_position(fragment, uniforms);
+                let mut glow = Vector3::zero();
+                for light in uniforms.lights.iter().filter(|light| light.has_position()) {
+                    let light_screen = project_world_to_screen(light.position, uniforms);
+                    let dist = (fragment.position - light_screen).length();
+                    let (_, radiance) = light.incident(position);
+                    glow += radiance * (screen_attenuation(dist, *radius) * strength);
+                }
+                glow
+            }
+            LayerSource::Lambert => {
+                let position = fragment_world_position(fragment, uniforms);
+                let mut intensity = 0.0;
+                for (light_dir, radiance) in uniforms.incident_lights(position, fragment.normal) {
+                    intensity += fragment.normal.dot(light_dir).max(0.0) * (radiance.x + radiance.y + radiance.z) / 3.0;
+                }
+                Vector3::new(intensity, intensity, intensity)
+            }
+        }
+    }
+}
+
+/// Cómo se combina una capa con lo acumulado por las capas anteriores.
+#[derive(Clone)]
+pub enum Blend {
+    Replace,
+    Add,
+    Multiply,
+    Screen,
+    // mezcla lineal entre lo acumulado y la capa, usando el canal x de la máscara
+    Mix(LayerSource),
+}
+
+#[derive(Clone)]
+pub struct Layer {
+    pub source: LayerSource,
+    pub blend: Blend,
+    pub opacity: f32,
+    // recorta lo acumulado a [0, 1] antes de aplicar esta capa
+    pub clamp: bool,
+}
+
+impl Layer {
+    pub fn new(blend: Blend, source: LayerSource) -> Self {
+        Layer { source, blend, opacity: 1.0, clamp: false }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Material {
+    pub layers: Vec<Layer>,
+}
+
+impl Material {
+    pub fn new(layers: Vec<Layer>) -> Self {
+        Material { layers }
+    }
+
+    /// Alguna capa lee las luces (y con eso sus sombras).
+    pub fn uses_lights(&self) -> bool {
+        self.layers.iter().any(|layer| {
+            let source_lit = |source: &LayerSource| match source {
+                LayerSource::FragmentColor | LayerSource::Lambert => true,
+                LayerSource::Shader { lit, .. } => *lit,
+                _ => false,
+            };
+            source_lit(&layer.source) || matches!(&layer.blend, Blend::Mix(mask) if source_lit(mask))
+        })
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        Material::parse(&text).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    /// Lee un material desde texto: una capa por línea, de abajo hacia arriba.
+    ///
+    /// ```text
+    /// # <blend> <fuente> [args], y en cualquier orden: [mask <fuente> [args]] [opacity <f>] [clamp]
+    /// replace color 1 0 1
+    /// add shader fragment_shader2
+    /// mix color 1 0 0 mask noise 0.05 0.8 42
+    /// multiply noise 0.05 0.8 42 clamp
+    /// ```
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut layers = Vec::new();
+
+        for (number, raw) in text.lines().enumerate() {
+            let line = raw.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let layer = parse_layer(line).map_err(|e| format!("línea {}: {}", number + 1, e))?;
+            layers.push(layer);
+        }
+
+        Ok(Material { layers })
+    }
+
+    /// Evalúa todas las capas una sola vez para este fragmento.
+    pub fn evaluate(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        let mut color = Vector3::zero();
+
+        for layer in &self.layers {
+            if layer.clamp {
+                color = Vector3::new(color.x.clamp(0.0, 1.0), color.y.clamp(0.0, 1.0), color.z.clamp(0.0, 1.0));
+            }
+
+            let value = layer.source.evaluate(fragment, uniforms);
+            let blended = match &layer.blend {
+                Blend::Replace => value,
+                Blend::Add => color + value,
+                Blend::Multiply => color * value,
+                Blend::Screen => Vector3::one() - (Vector3::one() - color) * (Vector3::one() - value),
+                Blend::Mix(mask) => {
+                    let t = mask.evaluate(fragment, uniforms).x.clamp(0.0, 1.0);
+                    color.lerp(value, t)
+                }
+            };
+
+            color = color.lerp(blended, layer.opacity);
+        }
+
+        color
+    }
+}
+
+fn parse_number(token: Option<&&str>, what: &str) -> Result<f32, String> {
+    let token = token.ok_or_else(|| format!("falta {}", what))?;
+    token.parse::<f32>().map_err(|_| format!("'{}' no es un número ({})", token, what))
+}
+
+fn parse_source(tokens: &[&str]) -> Result<LayerSource, String> {
+    let name = tokens.first().ok_or("falta la fuente de la capa")?;
+    let args = &tokens[1..];
+
+    let source = match *name {
+        "color" => LayerSource::Color(Vector3::new(
+            parse_number(args.first(), "r")?,
+            parse_number(args.get(1), "g")?,
+            parse_number(args.get(2), "b")?,
+        )),
+        "fragment_color" => LayerSource::FragmentColor,
+        "shader" => {
+            let shader_name = args.first().ok_or("falta el nombre del shader")?;
+            let shader = fragment_shader_by_name(shader_name)
+                .ok_or_else(|| format!("shader desconocido '{}'", shader_name))?;
+            LayerSource::Shader { shader, lit: shader_uses_lights(shader_name) }
+        }
+        "noise" => {
+            let seed = match args.get(2) {
+                Some(token) => token.parse::<u32>().map_err(|_| format!("'{}' no es una semilla válida", token))?,
+                None => 42,
+            };
+            LayerSource::noise(parse_number(args.first(), "frequency")?, parse_number(args.get(1), "speed")?, seed)
+        }
+        "glow" => LayerSource::Glow {
+            strength: parse_number(args.first(), "strength")?,
+            radius: if args.len() > 1 { parse_number(args.get(1), "radius")? } else { 200.0 },
+        },
+        "lambert" => LayerSource::Lambert,
+        other => return Err(format!("fuente desconocida '{}'", other)),
+    };
+
+    let max_args = match *name {
+        "color" | "noise" => 3,
+        "glow" => 2,
+        "shader" => 1,
+        _ => 0,
+    };
+    if args.len() > max_args {
+        return Err(format!("sobran valores después de '{}': '{}'", name, args[max_args..].join(" ")));
+    }
+
+    Ok(source)
+}
+
+fn parse_layer(line: &str) -> Result<Layer, String> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+
+    // Opciones después de la fuente, en cualquier orden: cada palabra clave corta la lista
+    let options = ["clamp", "opacity", "mask"];
+    let first_option = tokens.iter().position(|t| options.contains(t)).unwrap_or(tokens.len());
+    let (tokens, mut rest) = tokens.split_at(first_option);
+
+    let mut clamp = false;
+    let mut opacity = None;
+    let mut mask = None;
+    while let Some((option, after)) = rest.split_first() {
+        let end = after.iter().position(|t| options.contains(t)).unwrap_or(after.len());
+        let (args, next) = after.split_at(end);
+        match *option {
+            "clamp" if clamp => return Err("clamp repetido".to_string()),
+            "clamp" if !args.is_empty() => return Err(format!("clamp no lleva valores: '{}'", args.join(" "))),
+            "clamp" => clamp = true,
+            "opacity" if opacity.is_some() => return Err("opacity repetido".to_string()),
+            "opacity" if args.len() > 1 => return Err(format!("sobran valores después de opacity: '{}'", args[1..].join(" "))),
+            "opacity" => opacity = Some(parse_number(args.first(), "opacity")?),
+            _ if mask.is_some() => return Err("mask repetido".to_string()),
+            _ => mask = Some(parse_source(args)?),
+        }
+        rest = next;
+    }
+    let opacity = opacity.unwrap_or(1.0);
+
+    let blend_name = tokens.first().ok_or("línea vacía")?;
+    let source = parse_source(&tokens[1..])?;
+
+    let blend = match *blend_name {
+        "replace" => Blend::Replace,
+        "add" => Blend::Add,
+        "multiply" => Blend::Multiply,
+        "screen" => Blend::Screen,
+        "mix" => Blend::Mix(mask.take().ok_or("mix necesita una máscara: mix <fuente> mask <fuente>")?),
+        other => return Err(format!("operación desconocida '{}'", other)),
+    };
+
+    if mask.is_some() {
+        return Err("solo mix acepta una máscara".to_string());
+    }
+
+    Ok(Layer { source, blend, opacity, clamp })
+}
@@ -12,6 +12,12 @@ pub fn multiply_matrix_vector4(matrix: &Matrix, vector: &Vector4) -> Vector4 {
     )
 }
 
+/// Standard matrix product a·b (b is applied first).
+/// Raylib's `*` operator multiplies in the opposite order, so `a * b` there is b·a.
+pub fn multiply_matrices(a: &Matrix, b: &Matrix) -> Matrix {
+    *b * *a
+}
+
 /// Creates a 4x4 matrix from 16 float values, specified in traditional row-major order.
 pub fn new_matrix4(
     // Row 0
@@ -100,6 +106,12 @@ pub fn create_model_matrix(translation: Vector3, scale: f32, rotation: Vector3)
     translation_matrix * rotation_matrix * scale_matrix
 }
 
+/// Combines model, view and projection into a single MVP matrix (projection·view·model),
+/// so each vertex needs only one matrix multiply to reach clip space
+pub fn create_mvp_matrix(model: &Matrix, view: &Matrix, projection: &Matrix) -> Matrix {
+    multiply_matrices(projection, &multiply_matrices(view, model))
+}
+
 /// Creates a view matrix using camera position, target, and up vector
 /// This implements a lookAt matrix for camera transformations
 pub fn create_view_matrix(eye: Vector3, target: Vector3, up: Vector3) -> Matrix {
@@ -155,6 +167,26 @@ pub fn create_projection_matrix(fov_y: f32, aspect: f32, near: f32, far: f32) ->
     )
 }
 
+/// Creates an orthographic projection matrix for the given view-space box
+pub fn create_orthographic_matrix(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Matrix {
+    new_matrix4(
+        2.0 / (right - left), 0.0, 0.0, -(right + left) / (right - left),
+        0.0, 2.0 / (top - bottom), 0.0, -(top + bottom) / (top - bottom),
+        0.0, 0.0, -2.0 / (far - near), -(far + near) / (far - near),
+        0.0, 0.0, 0.0, 1.0,
+    )
+}
+
+/// Converts a depth stored in the framebuffer (NDC z) back to a linear distance from the
+/// camera, inverting the projection matrix's z/w rows
+pub fn linearize_depth(ndc_z: f32, projection: &Matrix) -> f32 {
+    let denominator = ndc_z * projection.m11 - projection.m10;
+    if denominator.abs() < 1e-8 {
+        return f32::INFINITY;
+    }
+    -(projection.m14 - ndc_z * projection.m15) / denominator
+}
+
 pub fn create_viewport_matrix(x: f32, y: f32, width: f32, height: f32) -> Matrix {
     let half_width = width / 2.0;
     let half_height = height / 2.0;
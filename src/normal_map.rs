@@ -0,0 +1,93 @@
+// normal_map.rs
+#![allow(dead_code)]
+
+use raylib::prelude::*;
+use noise::{Fbm, MultiFractal, NoiseFn, Perlin};
+use crate::Uniforms;
+use crate::fragment::Fragment;
+use crate::matrix::multiply_matrix_vector4;
+
+thread_local! {
+    static BUMPS: Fbm<Perlin> = Fbm::<Perlin>::new(5).set_octaves(4);
+}
+
+/// Normal map en espacio tangente, decodificado de [0, 255] a [-1, 1] al cargarlo
+/// para no leer la imagen de raylib por cada fragmento.
+pub struct NormalMap {
+    pub width: i32,
+    pub height: i32,
+    texels: Vec<Vector3>,
+}
+
+impl NormalMap {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let mut image = Image::load_image(path).map_err(|e| format!("{}: {}", path, e))?;
+        let width = image.width();
+        let height = image.height();
+
+        let mut texels = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let color = image.get_color(x, y);
+                texels.push(Vector3::new(
+                    color.r as f32 / 255.0 * 2.0 - 1.0,
+                    color.g as f32 / 255.0 * 2.0 - 1.0,
+                    color.b as f32 / 255.0 * 2.0 - 1.0,
+                ));
+            }
+        }
+
+        Ok(NormalMap { width, height, texels })
+    }
+
+    fn texel(&self, x: i32, y: i32) -> Vector3 {
+        let x = x.rem_euclid(self.width);
+        let y = y.rem_euclid(self.height);
+        self.texels[(y * self.width + x) as usize]
+    }
+
+    /// Muestreo bilineal con repetición (wrap); devuelve la normal en espacio tangente.
+    pub fn sample(&self, uv: Vector2) -> Vector3 {
+        // v crece hacia arriba en las UVs y hacia abajo en la imagen
+        let x = uv.x * self.width as f32 - 0.5;
+        let y = (1.0 - uv.y) * self.height as f32 - 0.5;
+        let x0 = x.floor();
+        let y0 = y.floor();
+        let fx = x - x0;
+        let fy = y - y0;
+
+        let top = self.texel(x0 as i32, y0 as i32).lerp(self.texel(x0 as i32 + 1, y0 as i32), fx);
+        let bottom = self.texel(x0 as i32, y0 as i32 + 1).lerp(self.texel(x0 as i32 + 1, y0 as i32 + 1), fx);
+        top.lerp(bottom, fy).normalized()
+    }
+}
+
+/// Lleva una normal en espacio tangente al espacio del marco TBN dado.
+pub fn perturb_normal(normal: Vector3, tangent: Vector3, bitangent: Vector3, tangent_space: Vector3) -> Vector3 {
+    let perturbed = tangent * tangent_space.x + bitangent * tangent_space.y + normal * tangent_space.z;
+    if perturbed.length() > 0.0 { perturbed.normalized() } else { normal }
+}
+
+/// Bump procedural: perturba `normal` con las derivadas de un ruido evaluado en espacio
+/// objeto. El gradiente se pasa a espacio mundo y se proyecta sobre la tangente y la
+/// bitangente del fragmento.
+pub fn noise_bump_normal(fragment: &Fragment, normal: Vector3, uniforms: &Uniforms, frequency: f32, strength: f32) -> Vector3 {
+    let p = fragment.vertex_position * frequency;
+    let height = |q: Vector3| BUMPS.with(|n| n.get([q.x as f64, q.y as f64, q.z as f64])) as f32;
+
+    // diferencias centrales en espacio objeto
+    let eps = 0.01;
+    let gradient = Vector3::new(
+        height(p + Vector3::new(eps, 0.0, 0.0)) - height(p - Vector3::new(eps, 0.0, 0.0)),
+        height(p + Vector3::new(0.0, eps, 0.0)) - height(p - Vector3::new(0.0, eps, 0.0)),
+        height(p + Vector3::new(0.0, 0.0, eps)) - height(p - Vector3::new(0.0, 0.0, eps)),
+    ) * (frequency / (2.0 * eps));
+
+    let world = multiply_matrix_vector4(&uniforms.model_matrix, &Vector4::new(gradient.x, gradient.y, gradient.z, 0.0));
+    let gradient = Vector3::new(world.x, world.y, world.z);
+
+    let dh_du = gradient.dot(fragment.tangent);
+    let dh_dv = gradient.dot(fragment.bitangent);
+    let perturbed = normal - (fragment.tangent * dh_du + fragment.bitangent * dh_dv) * strength;
+    if perturbed.length() > 0.0 { perturbed.normalized() } else { normal }
+}
@@ -1,17 +1,21 @@
-
 use raylib::math::{Vector2,Vector3};
 use tobj;
 
+use crate::bounds::BoundingVolume;
+use crate::surface::SurfaceMaterial;
 use crate::vertex::Vertex;
 
 pub struct Obj {
     pub vertices: Vec<Vertex>,
     pub indices: Vec<u32>,
+    pub material: SurfaceMaterial,
+    // esfera y caja que envuelven la malla, para descartar el objeto si queda fuera de la vista
+    pub bounds: BoundingVolume,
 }
 
 impl Obj {
     pub fn load(path: &str) -> Result<Self, tobj::LoadError> {
-        let (models, _materials) = tobj::load_obj(path, &tobj::GPU_LOAD_OPTIONS)?;
+        let (models, materials) = tobj::load_obj(path, &tobj::GPU_LOAD_OPTIONS)?;
 
         let mut vertices = Vec::new();
         let mut indices = Vec::new();
@@ -19,6 +23,8 @@ impl Obj {
         for model in models {
             let mesh = &model.mesh;
             let num_vertices = mesh.positions.len() / 3;
+            // los índices de cada malla empiezan en 0
+            let base_index = vertices.len() as u32;
 
             for i in 0..num_vertices {
                 let x = mesh.positions[i * 3];
@@ -26,11 +32,12 @@ impl Obj {
                 let z = mesh.positions[i * 3 + 2];
                 let position = Vector3::new(x, -y, z);
 
+                // la normal se refleja igual que la posición para que sigan siendo coherentes
                 let normal = if !mesh.normals.is_empty() {
                     let nx = mesh.normals[i * 3];
                     let ny = mesh.normals[i * 3 + 1];
                     let nz = mesh.normals[i * 3 + 2];
-                    Vector3::new(nx, ny, nz)
+                    Vector3::new(nx, -ny, nz)
                 } else {
                     Vector3::zero()
                 };
@@ -45,10 +52,19 @@ impl Obj {
 
                 vertices.push(Vertex::new(position, normal, tex_coords));
             }
-            indices.extend_from_slice(&mesh.indices);
+            indices.extend(mesh.indices.iter().map(|index| index + base_index));
         }
 
-        Ok(Obj { vertices, indices })
+        compute_tangents(&mut vertices, &indices);
+
+        // Un material por objeto: el primero del .mtl. Si falta el archivo, el material por defecto
+        let material = materials.ok()
+            .and_then(|materials| materials.first().map(SurfaceMaterial::from_mtl))
+            .unwrap_or_default();
+
+        let bounds = BoundingVolume::from_vertices(&vertices);
+
+        Ok(Obj { vertices, indices, material, bounds })
     }
 
     pub fn get_vertex_array(&self) -> Vec<Vertex> {
@@ -58,4 +74,73 @@ impl Obj {
         }
         vertex_array
     }
-}
\ No newline at end of file
+}
+
+// Ángulo de la esquina del triángulo entre los lados a y b
+fn corner_angle(a: Vector3, b: Vector3) -> f32 {
+    let len = a.length() * b.length();
+    if len <= 0.0 {
+        return 0.0;
+    }
+    (a.dot(b) / len).clamp(-1.0, 1.0).acos()
+}
+
+/// Calcula tangente y bitangente por vértice a partir de posiciones y UVs.
+/// Igual que MikkTSpace, cada triángulo aporta su tangente pesada por el ángulo de la
+/// esquina, luego se ortogonaliza contra la normal (Gram-Schmidt) y la bitangente sale
+/// de N x T con el signo de la orientación de las UVs (espejos).
+fn compute_tangents(vertices: &mut [Vertex], indices: &[u32]) {
+    let mut tangents = vec![Vector3::zero(); vertices.len()];
+    let mut bitangents = vec![Vector3::zero(); vertices.len()];
+
+    for tri in indices.chunks_exact(3) {
+        let [i0, i1, i2] = [tri[0] as usize, tri[1] as usize, tri[2] as usize];
+        let (p0, p1, p2) = (vertices[i0].position, vertices[i1].position, vertices[i2].position);
+        let (uv0, uv1, uv2) = (vertices[i0].tex_coords, vertices[i1].tex_coords, vertices[i2].tex_coords);
+
+        let edge1 = p1 - p0;
+        let edge2 = p2 - p0;
+        let du1 = uv1.x - uv0.x;
+        let dv1 = uv1.y - uv0.y;
+        let du2 = uv2.x - uv0.x;
+        let dv2 = uv2.y - uv0.y;
+
+        let det = du1 * dv2 - du2 * dv1;
+        if det.abs() < 1e-10 {
+            continue;
+        }
+        let r = 1.0 / det;
+        let tangent = (edge1 * dv2 - edge2 * dv1) * r;
+        let bitangent = (edge2 * du1 - edge1 * du2) * r;
+
+        let weights = [
+            corner_angle(p1 - p0, p2 - p0),
+            corner_angle(p2 - p1, p0 - p1),
+            corner_angle(p0 - p2, p1 - p2),
+        ];
+        for (corner, &index) in [i0, i1, i2].iter().enumerate() {
+            tangents[index] += tangent * weights[corner];
+            bitangents[index] += bitangent * weights[corner];
+        }
+    }
+
+    for (i, vertex) in vertices.iter_mut().enumerate() {
+        if vertex.normal.length() <= 0.0 {
+            continue;
+        }
+        let normal = vertex.normal.normalized();
+
+        // Gram-Schmidt: quitamos la componente sobre la normal
+        let mut tangent = tangents[i] - normal * normal.dot(tangents[i]);
+        if tangent.length() < 1e-6 {
+            // sin UVs útiles: cualquier vector perpendicular sirve
+            tangent = normal.perpendicular();
+        }
+        let tangent = tangent.normalized();
+
+        let handedness = if normal.cross(tangent).dot(bitangents[i]) < 0.0 { -1.0 } else { 1.0 };
+
+        vertex.tangent = tangent;
+        vertex.bitangent = normal.cross(tangent) * handedness;
+    }
+}
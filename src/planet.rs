@@ -0,0 +1,191 @@
+// planet.rs
+#![allow(dead_code)]
+
+use raylib::prelude::*;
+use noise::{Fbm, MultiFractal, NoiseFn, Perlin};
+use crate::Uniforms;
+use crate::ambient::ambient_light;
+use crate::clouds::cloud_shadow;
+use crate::fragment::Fragment;
+use crate::matrix::multiply_matrix_vector4;
+use crate::normal_map::{noise_bump_normal, perturb_normal};
+use crate::shaders::smoothstep;
+use crate::surface::fragment_world_position;
+
+thread_local! {
+    static TERRAIN: Fbm<Perlin> = Fbm::<Perlin>::new(3).set_octaves(6);
+    static DETAIL: Perlin = Perlin::new(17);
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct NightParams {
+    pub terminator_width: f32,   // ancho de la transición día/noche, en unidades de N·L
+    pub twilight_color: Vector3, // color de la franja del crepúsculo
+    pub twilight_strength: f32,
+    pub emission_strength: f32,  // brillo del patrón emisivo del lado nocturno
+    pub ambient: f32,            // cuánto de la luz ambiente de la escena recibe el planeta
+}
+
+impl Default for NightParams {
+    fn default() -> Self {
+        NightParams {
+            terminator_width: 0.15,
+            twilight_color: Vector3::new(1.0, 0.45, 0.2),
+            twilight_strength: 0.35,
+            emission_strength: 1.0,
+            ambient: 1.0,
+        }
+    }
+}
+
+// Altura del terreno en [-1, 1] aprox; bajo 0 es océano
+fn terrain_height(dir: Vector3) -> f32 {
+    let p = dir * 1.6;
+    TERRAIN.with(|n| n.get([p.x as f64, p.y as f64, p.z as f64])) as f32
+}
+
+/// Color diurno de un planeta tipo Tierra para una dirección en espacio objeto:
+/// océanos, costas, continentes y casquetes polares.
+pub fn earth_day_color(dir: Vector3) -> Vector3 {
+    let height = terrain_height(dir);
+
+    let deep = Vector3::new(0.02, 0.08, 0.3);
+    let shallow = Vector3::new(0.05, 0.3, 0.55);
+    let sand = Vector3::new(0.76, 0.7, 0.5);
+    let grass = Vector3::new(0.15, 0.45, 0.12);
+    let mountain = Vector3::new(0.4, 0.32, 0.25);
+    let ice = Vector3::new(0.92, 0.95, 1.0);
+
+    let color = if height < 0.0 {
+        deep.lerp(shallow, smoothstep(-0.35, 0.0, height))
+    } else if height < 0.04 {
+        sand
+    } else {
+        grass.lerp(mountain, smoothstep(0.15, 0.45, height))
+    };
+
+    // casquetes polares según la latitud
+    let polar = smoothstep(0.8, 0.9, dir.y.abs());
+    color.lerp(ice, polar)
+}
+
+/// Luces de ciudades: cúmulos de alta frecuencia, solo sobre tierra firme y lejos de los polos.
+pub fn city_lights(dir: Vector3) -> Vector3 {
+    let height = terrain_height(dir);
+    let land = smoothstep(0.02, 0.08, height) * (1.0 - smoothstep(0.3, 0.45, height));
+    let habitable = 1.0 - smoothstep(0.65, 0.8, dir.y.abs());
+
+    let p = dir * 22.0;
+    let clusters = DETAIL.with(|n| n.get([p.x as f64, p.y as f64, p.z as f64])) as f32;
+    let q = dir * 70.0;
+    let sparkle = DETAIL.with(|n| n.get([q.x as f64, q.y as f64, q.z as f64])) as f32;
+    let density = smoothstep(0.15, 0.45, clusters) * smoothstep(0.0, 0.3, sparkle);
+
+    Vector3::new(1.0, 0.8, 0.45) * (density * land * habitable)
+}
+
+/// Color diurno de un planeta volcánico: roca basáltica oscura con grietas.
+pub fn lava_day_color(dir: Vector3) -> Vector3 {
+    let crack = lava_cracks(dir);
+    let rock = Vector3::new(0.16, 0.13, 0.12).lerp(Vector3::new(0.3, 0.26, 0.22), (terrain_height(dir) + 1.0) * 0.5);
+    rock.lerp(Vector3::new(0.6, 0.2, 0.05), crack)
+}
+
+// Máscara de grietas de lava en [0, 1]: crestas finas de ruido
+fn lava_cracks(dir: Vector3) -> f32 {
+    let p = dir * 6.0;
+    let ridge = DETAIL.with(|n| n.get([p.x as f64, p.y as f64, p.z as f64])) as f32;
+    1.0 - smoothstep(0.0, 0.08, ridge.abs())
+}
+
+/// Brillo de la lava en el lado nocturno; pulsa lentamente con el tiempo.
+pub fn lava_glow(dir: Vector3, time: f32) -> Vector3 {
+    let p = dir * 3.0;
+    let pulse = DETAIL.with(|n| n.get([p.x as f64, p.y as f64, p.z as f64 + (time * 0.3) as f64])) as f32;
+    let heat = 0.6 + 0.4 * (pulse + 1.0) * 0.5;
+    Vector3::new(1.0, 0.35, 0.05) * (lava_cracks(dir) * heat)
+}
+
+/// Mezcla el color diurno iluminado con el patrón emisivo nocturno según N·L de cada luz,
+/// con un terminador suave y una franja de crepúsculo alrededor de N·L = 0. `shadow`
+/// recibe la dirección de una luz y devuelve cuánto la dejan pasar las nubes; las sombras
+/// de otros objetos salen de los shadow maps en `position`. `occlusion` (del SSAO) solo
+/// afecta a la luz ambiente.
+pub fn shade_day_night(day: Vector3, night: Vector3, surface_dir: Vector3, position: Vector3, occlusion: f32, uniforms: &Uniforms, shadow: &dyn Fn(Vector3) -> f32) -> Vector3 {
+    let params = &uniforms.night;
+    let width = params.terminator_width.max(1e-3);
+    let center = planet_center(uniforms);
+
+    let mut lit = day * ambient_light(surface_dir, uniforms) * (params.ambient * occlusion);
+    let mut twilight = Vector3::zero();
+    // la luz más fuerte decide cuánto se ve el lado nocturno
+    let mut daylight: f32 = 0.0;
+    for (index, light) in uniforms.lights.iter().enumerate() {
+        let (light_dir, radiance) = light.incident(center);
+        let n_dot_l = surface_dir.dot(light_dir);
+        let brightness = ((radiance.x + radiance.y + radiance.z) / 3.0).min(1.0);
+
+        daylight = daylight.max(smoothstep(-width, width, n_dot_l) * brightness);
+        let occlusion = shadow(light_dir) * uniforms.shadow_visibility(index, position, surface_dir);
+        lit += day * radiance * (n_dot_l.max(0.0) * occlusion);
+
+        let band = n_dot_l / width;
+        twilight += params.twilight_color * radiance * ((-band * band).exp() * params.twilight_strength);
+    }
+
+    lit + twilight * day + night * ((1.0 - daylight) * params.emission_strength)
+}
+
+fn planet_center(uniforms: &Uniforms) -> Vector3 {
+    Vector3::new(uniforms.model_matrix.m12, uniforms.model_matrix.m13, uniforms.model_matrix.m14)
+}
+
+// Dirección de la superficie en espacio mundo, relativa al centro del planeta
+fn surface_direction(dir: Vector3, uniforms: &Uniforms) -> Vector3 {
+    let surface = multiply_matrix_vector4(&uniforms.model_matrix, &Vector4::new(dir.x, dir.y, dir.z, 0.0));
+    Vector3::new(surface.x, surface.y, surface.z).normalized()
+}
+
+pub fn earth_fragment_shader(fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+    let dir = fragment.vertex_position.normalized();
+    let surface_dir = surface_direction(dir, uniforms);
+    let position = fragment_world_position(fragment, uniforms);
+    let occlusion = uniforms.ambient_occlusion_at(fragment.position);
+    let shadow = |light_dir: Vector3| cloud_shadow(surface_dir, light_dir, uniforms);
+
+    shade_day_night(earth_day_color(dir), city_lights(dir), surface_dir, position, occlusion, uniforms, &shadow)
+}
+
+pub fn lava_fragment_shader(fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+    let dir = fragment.vertex_position.normalized();
+    let surface_dir = surface_direction(dir, uniforms);
+
+    let position = fragment_world_position(fragment, uniforms);
+    let occlusion = uniforms.ambient_occlusion_at(fragment.position);
+
+    shade_day_night(lava_day_color(dir), lava_glow(dir, uniforms.time), surface_dir, position, occlusion, uniforms, &|_| 1.0)
+}
+
+/// Luna rocosa: el relieve sale del normal map (cráteres) más un bump procedural fino,
+/// sin agregar triángulos a la esfera.
+pub fn moon_fragment_shader(fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+    let mut normal = fragment.normal;
+    if let Some(normal_map) = &uniforms.normal_map {
+        let tangent_space = normal_map.sample(fragment.tex_coords * 3.0);
+        normal = perturb_normal(normal, fragment.tangent, fragment.bitangent, tangent_space);
+    }
+    normal = noise_bump_normal(fragment, normal, uniforms, 12.0, 0.015);
+
+    let dir = fragment.vertex_position.normalized();
+    let albedo = Vector3::new(0.55, 0.53, 0.5) * (0.75 + 0.25 * (terrain_height(dir) + 1.0) * 0.5);
+
+    let world = multiply_matrix_vector4(&uniforms.model_matrix, &Vector4::new(fragment.vertex_position.x, fragment.vertex_position.y, fragment.vertex_position.z, 1.0));
+    let world_pos = Vector3::new(world.x, world.y, world.z);
+    let mut diffuse = Vector3::zero();
+    for (light_dir, radiance) in uniforms.incident_lights(world_pos, normal) {
+        diffuse += radiance * normal.dot(light_dir).max(0.0);
+    }
+
+    let ambient = ambient_light(normal, uniforms) * uniforms.ambient_occlusion_at(fragment.position);
+    albedo * (diffuse + ambient)
+}
@@ -1,13 +1,14 @@
 //shaders.rs
 use raylib::prelude::*;
-use crate::{light::Light, vertex::Vertex};
+use crate::vertex::Vertex;
 use crate::Uniforms;
 use crate::fragment::Fragment;
-use noise::{NoiseFn, Simplex, SuperSimplex};
+use crate::matrix::multiply_matrix_vector4;
+use crate::surface::lit_fragment_shader;
 // use rand::random;
 
 
-fn project_world_to_screen(light_pos: Vector3, uniforms: &Uniforms) -> Vector2 {
+pub fn project_world_to_screen(light_pos: Vector3, uniforms: &Uniforms) -> Vector2 {
     // light_pos -> vec4
     let pos4 = Vector4::new(light_pos.x, light_pos.y, light_pos.z, 1.0);
     // world -> view
@@ -27,34 +28,32 @@ fn project_world_to_screen(light_pos: Vector3, uniforms: &Uniforms) -> Vector2 {
 }
 
 // attenuation simple tipo gaussian para glow en pantalla
-fn screen_attenuation(dist: f32, range: f32) -> f32 {
+pub fn screen_attenuation(dist: f32, range: f32) -> f32 {
     // clamp para evitar división por cero; la forma gaussian da un bonito fade
     let r = if range <= 0.0 { 1.0 } else { range };
     (- (dist * dist) / (2.0 * r * r)).exp()
 }
 
-fn multiply_matrix_vector4(matrix: &Matrix, vector: &Vector4) -> Vector4 {
-    Vector4::new(
-        matrix.m0 * vector.x + matrix.m4 * vector.y + matrix.m8 * vector.z + matrix.m12 * vector.w,
-        matrix.m1 * vector.x + matrix.m5 * vector.y + matrix.m9 * vector.z + matrix.m13 * vector.w,
-        matrix.m2 * vector.x + matrix.m6 * vector.y + matrix.m10 * vector.z + matrix.m14 * vector.w,
-        matrix.m3 * vector.x + matrix.m7 * vector.y + matrix.m11 * vector.z + matrix.m15 * vector.w,
-    )
+pub fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
 }
 
-pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
-  let position_vec4 = Vector4::new(
-    vertex.position.x, //tan
-    vertex.position.y, //cos
-    vertex.position.z,
-    1.0
-  );
+// pseudo aleatorio determinista en [0, 1)
+pub fn hash(n: f32) -> f32 {
+    ((n.sin() * 43758.547).fract()).abs()
+}
 
-  let world_position = multiply_matrix_vector4(&uniforms.model_matrix, &position_vec4);
+/// Hook de un vertex shader: recibe el vértice original y devuelve su posición y normal
+/// en espacio objeto. El resto de la transformación lo hace `transform_vertex`.
+pub type VertexShader = fn(&Vertex, &Uniforms) -> (Vector3, Vector3);
 
-  let view_position = multiply_matrix_vector4(&uniforms.view_matrix, &world_position);
+/// Etapa de transformación común: objeto -> clip con la MVP precalculada, división de
+/// perspectiva y viewport. La normal se lleva a espacio mundo con la matriz de modelo.
+pub fn transform_vertex(vertex: &Vertex, position: Vector3, normal: Vector3, uniforms: &Uniforms) -> Vertex {
+  let position_vec4 = Vector4::new(position.x, position.y, position.z, 1.0);
 
-  let clip_position = multiply_matrix_vector4(&uniforms.projection_matrix, &view_position);
+  let clip_position = multiply_matrix_vector4(&uniforms.mvp_matrix, &position_vec4);
 
   let ndc = if clip_position.w != 0.0 {
       Vector3::new(
@@ -76,99 +75,30 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
   );
 
   Vertex {
-    position: vertex.position,
-    normal: vertex.normal,
+    position,
+    normal,
     tex_coords: vertex.tex_coords,
     color: vertex.color,
+    tangent: vertex.tangent,
+    bitangent: vertex.bitangent,
     transformed_position,
-    transformed_normal: transform_normal(&vertex.normal, &uniforms.model_matrix),
+    transformed_normal: transform_normal(&normal, &uniforms.model_matrix),
+    transformed_tangent: transform_normal(&vertex.tangent, &uniforms.model_matrix),
+    transformed_bitangent: transform_normal(&vertex.bitangent, &uniforms.model_matrix),
   }
 }
 
-pub fn vertex_shader2(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
-  let position_vec4 = Vector4::new(
-    vertex.position.x, //tan
-    vertex.position.y, //cos
-    vertex.position.z,
-    1.0
-  );
-
-  let world_position = multiply_matrix_vector4(&uniforms.model_matrix, &position_vec4);
-
-  let view_position = multiply_matrix_vector4(&uniforms.view_matrix, &world_position);
-
-  let clip_position = multiply_matrix_vector4(&uniforms.projection_matrix, &view_position);
-
-  let ndc = if clip_position.w != 0.0 {
-      Vector3::new(
-          clip_position.x / clip_position.w,
-          clip_position.y / clip_position.w,
-          clip_position.z / clip_position.w,
-      )
-  } else {
-      Vector3::new(clip_position.x, clip_position.y, clip_position.z)
-  };
-
-  let ndc_vec4 = Vector4::new(ndc.x, ndc.y, ndc.z, 1.0);
-  let screen_position = multiply_matrix_vector4(&uniforms.viewport_matrix, &ndc_vec4);
-
-  let transformed_position = Vector3::new(
-      screen_position.x,
-      screen_position.y,
-      screen_position.z,
-  );
-
-  Vertex {
-    position: vertex.position,
-    normal: vertex.normal,
-    tex_coords: vertex.tex_coords,
-    color: vertex.color,
-    transformed_position,
-    transformed_normal: transform_normal(&vertex.normal, &uniforms.model_matrix),
-  }
+pub fn vertex_shader(vertex: &Vertex, _uniforms: &Uniforms) -> (Vector3, Vector3) {
+  (vertex.position, vertex.normal)
 }
 
-pub fn vertex_shader3(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
-  let position_vec4 = Vector4::new(
-    vertex.position.x.tan(), 
-    vertex.position.y.cos(), 
+pub fn vertex_shader3(vertex: &Vertex, _uniforms: &Uniforms) -> (Vector3, Vector3) {
+  let position = Vector3::new(
+    vertex.position.x.tan(),
+    vertex.position.y.cos(),
     vertex.position.z,
-    1.0
   );
-
-  let world_position = multiply_matrix_vector4(&uniforms.model_matrix, &position_vec4);
-
-  let view_position = multiply_matrix_vector4(&uniforms.view_matrix, &world_position);
-
-  let clip_position = multiply_matrix_vector4(&uniforms.projection_matrix, &view_position);
-
-  let ndc = if clip_position.w != 0.0 {
-      Vector3::new(
-          clip_position.x / clip_position.w,
-          clip_position.y / clip_position.w,
-          clip_position.z / clip_position.w,
-      )
-  } else {
-      Vector3::new(clip_position.x, clip_position.y, clip_position.z)
-  };
-
-  let ndc_vec4 = Vector4::new(ndc.x, ndc.y, ndc.z, 1.0);
-  let screen_position = multiply_matrix_vector4(&uniforms.viewport_matrix, &ndc_vec4);
-
-  let transformed_position = Vector3::new(
-      screen_position.x,
-      screen_position.y,
-      screen_position.z,
-  );
-
-  Vertex {
-    position: vertex.position,
-    normal: vertex.normal,
-    tex_coords: vertex.tex_coords,
-    color: vertex.color,
-    transformed_position,
-    transformed_normal: transform_normal(&vertex.normal, &uniforms.model_matrix),
-  }
+  (position, vertex.normal)
 }
 
 fn transform_normal(normal: &Vector3, model_matrix: &Matrix) -> Vector3 {
@@ -185,8 +115,8 @@ fn transform_normal(normal: &Vector3, model_matrix: &Matrix) -> Vector3 {
     transformed_normal.normalize();
     transformed_normal
 }
-pub fn fragment_shader1(fragment: &Fragment, uniforms: &Uniforms, light:&Light) -> Vector3 {
-    let base_color = fragment.color+0.2;
+pub fn fragment_shader1(fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+    let base_color = lit_fragment_shader(fragment, uniforms)+0.2;
 
     let x_pattern = (fragment.position.x / 20.0) * 0.35 + 0.1;
     let y_pattern = (fragment.position.y / 250.0).cos() * 0.9 + 0.1;
@@ -200,8 +130,8 @@ pub fn fragment_shader1(fragment: &Fragment, uniforms: &Uniforms, light:&Light)
     base_color * 0.4 + pattern_color * 0.6
 }
 
-pub fn fragment_shader2(fragment: &Fragment, uniforms: &Uniforms, light:&Light) -> Vector3 {
-    let base_color = fragment.color-0.6;
+pub fn fragment_shader2(fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+    let base_color = lit_fragment_shader(fragment, uniforms)-0.6;
 
     let x_pattern = (fragment.position.x / 20.0).sin() * 0.5 + 0.5;
     let y_pattern = (fragment.position.y / 10.0).cos() * 0.1 + 0.3;
@@ -215,7 +145,7 @@ pub fn fragment_shader2(fragment: &Fragment, uniforms: &Uniforms, light:&Light)
     base_color * 0.5 + pattern_color * 0.5
 }
 
-pub fn fragment_shader3(fragment: &Fragment, uniforms: &Uniforms, light:&Light) -> Vector3 {
+pub fn fragment_shader3(fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
     let base_color = Vector3::new(0.0, 0.8, 0.6); // verde turquesa base
 
     let ripple = ((fragment.position.x * 0.05).sin() + (fragment.position.y * 0.05).cos()) * 0.5 + 0.5;
@@ -229,84 +159,14 @@ pub fn fragment_shader3(fragment: &Fragment, uniforms: &Uniforms, light:&Light)
     wave_color
 }
 
-pub fn ultra_mega_vertex_shader(vertex: &Vertex, uniforms: &Uniforms) ->Vertex{
-  let position_vec4 = Vector4::new(
-    vertex_shader(vertex, uniforms).position.x * vertex_shader2(vertex, uniforms).position.x * vertex_shader3(vertex, uniforms).position.x *3.5, 
-    vertex_shader(vertex, uniforms).position.y * vertex_shader2(vertex, uniforms).position.y * vertex_shader3(vertex, uniforms).position.y *3.5, 
-    vertex_shader(vertex, uniforms).position.z * vertex_shader2(vertex, uniforms).position.z * vertex_shader3(vertex, uniforms).position.z *3.5,
-    1.0
+// Antes combinaba vertex_shader * vertex_shader2 * vertex_shader3 por componente; como
+// esos no cambian `position`, el resultado es la posición al cubo escalada.
+pub fn ultra_mega_vertex_shader(vertex: &Vertex, _uniforms: &Uniforms) -> (Vector3, Vector3) {
+  let p = vertex.position;
+  let position = Vector3::new(
+    p.x * p.x * p.x * 3.5,
+    p.y * p.y * p.y * 3.5,
+    p.z * p.z * p.z * 3.5,
   );
-
-  let world_position = multiply_matrix_vector4(&uniforms.model_matrix, &position_vec4);
-
-  let view_position = multiply_matrix_vector4(&uniforms.view_matrix, &world_position);
-
-  let clip_position = multiply_matrix_vector4(&uniforms.projection_matrix, &view_position);
-
-  let ndc = if clip_position.w != 0.0 {
-      Vector3::new(
-          clip_position.x / clip_position.w,
-          clip_position.y / clip_position.w,
-          clip_position.z / clip_position.w,
-      )
-  } else {
-      Vector3::new(clip_position.x, clip_position.y, clip_position.z)
-  };
-
-  let ndc_vec4 = Vector4::new(ndc.x, ndc.y, ndc.z, 1.0);
-  let screen_position = multiply_matrix_vector4(&uniforms.viewport_matrix, &ndc_vec4);
-
-  let transformed_position = Vector3::new(
-      screen_position.x,
-      screen_position.y,
-      screen_position.z,
-  );
-
-  Vertex {
-    position: vertex.position,
-    normal: vertex.normal,
-    tex_coords: vertex.tex_coords,
-    color: vertex.color,
-    transformed_position,
-    transformed_normal: transform_normal(&vertex.normal, &uniforms.model_matrix),
-  }
+  (position, vertex.normal)
 }
-
-pub fn ultra_mega_fragment_shader(fragment: &Fragment, uniforms: &Uniforms, light:&Light) -> Vector3 {
-    let simplex: SuperSimplex= SuperSimplex::new(42); 
-    let freq = 0.05;
-
-    let x = fragment.position.x * freq;
-    let y = fragment.position.y * freq;
-    let t = uniforms.time * 0.8;
-
-    let raw = simplex.get([x as f64, y as f64, t as f64]);
-    let noise_val = ((raw + 1.0) * 0.5) as f32;
-
-    let color = 
-        // fragment_shader1(fragment, uniforms)+
-        Vector3::new(1.0, 0.0, 1.0)     //R-G-B
-        + fragment_shader2(fragment, uniforms,light)
-        + fragment_shader3(fragment, uniforms,light)
-        ;
-
-    let light_screen = project_world_to_screen(light.position, uniforms);
-    let dx = fragment.position.x - light_screen.x;
-    let dy = fragment.position.y - light_screen.y;
-    let dist = (dx * dx + dy * dy).sqrt();
-
-    let local_light = 5.0;
-    // rango en píxeles: usa light.range
-    let att = screen_attenuation(dist, light.range) * light.intensity * local_light;
-    
-    // sumar emisión (additiva) con el color de la luz
-    let mut color2 = color + light.color * att;
-
-      color2 = Vector3::new(
-        color2.x.min(1.0),
-        color2.y.min(1.0),
-        color2.z.min(1.0),
-    );
-
-    color2 * Vector3::new(noise_val, noise_val, noise_val)
-}
\ No newline at end of file
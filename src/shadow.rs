@@ -0,0 +1,259 @@
+// shadow.rs
+#![allow(dead_code)]
+
+use raylib::prelude::*;
+use crate::light::{Light, LightKind};
+use crate::matrix::{create_orthographic_matrix, create_projection_matrix, create_view_matrix, linearize_depth, multiply_matrices, multiply_matrix_vector4};
+use crate::shaders::VertexShader;
+use crate::vertex::Vertex;
+use crate::Uniforms;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ShadowSettings {
+    pub resolution: i32,   // lado de cada cara del mapa, en texels
+    pub bias: f32,         // margen de profundidad en unidades de mundo (evita el shadow acne)
+    pub normal_bias: f32,  // desplaza el punto consultado a lo largo de la normal
+    pub pcf_radius: i32,   // radio del filtro PCF en texels (0 = sin filtrar)
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        ShadowSettings {
+            resolution: 512,
+            bias: 0.05,
+            normal_bias: 0.03,
+            pcf_radius: 1,
+        }
+    }
+}
+
+/// Un objeto que proyecta sombra: su matriz de modelo, sus vértices (en triángulos) y su
+/// vertex shader, para que la sombra tenga la misma forma que la malla que se dibuja.
+pub struct ShadowCaster<'a> {
+    pub model_matrix: Matrix,
+    pub vertices: &'a [Vertex],
+    pub vertex_shader: VertexShader,
+}
+
+// Una vista de la luz: la matriz view-projection y la distancia lineal más cercana por texel
+struct ShadowFace {
+    view_projection: Matrix,
+    projection: Matrix,
+    depth: Vec<f32>,
+}
+
+/// Mapa de sombras de una luz: 6 caras (cubo) para luces puntuales, una ortográfica para
+/// las direccionales y una en perspectiva para los focos.
+pub struct ShadowMap {
+    faces: Vec<ShadowFace>,
+    settings: ShadowSettings,
+    kind: LightKind,
+    position: Vector3,
+}
+
+// Un vector "arriba" que no sea paralelo a la dirección de la vista
+fn up_for(direction: Vector3) -> Vector3 {
+    if direction.y.abs() > 0.99 { Vector3::new(0.0, 0.0, 1.0) } else { Vector3::new(0.0, 1.0, 0.0) }
+}
+
+// Esfera que envuelve a todos los objetos, para encuadrar la vista ortográfica
+fn scene_bounds(world_triangles: &[[Vector3; 3]]) -> (Vector3, f32) {
+    let mut min = Vector3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+    let mut max = Vector3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY);
+    for p in world_triangles.iter().flatten() {
+        min = Vector3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+        max = Vector3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+    }
+    let center = (min + max) * 0.5;
+    (center, ((max - min) * 0.5).length().max(0.1))
+}
+
+impl ShadowFace {
+    fn new(view: Matrix, projection: Matrix, resolution: i32) -> Self {
+        ShadowFace {
+            view_projection: multiply_matrices(&projection, &view),
+            projection,
+            depth: vec![f32::INFINITY; (resolution * resolution) as usize],
+        }
+    }
+
+    // Punto de mundo -> (x, y) en texels y z en NDC; None si queda detrás de la luz
+    fn project(&self, point: Vector3, resolution: i32) -> Option<Vector3> {
+        let clip = multiply_matrix_vector4(&self.view_projection, &Vector4::new(point.x, point.y, point.z, 1.0));
+        if clip.w <= 1e-6 {
+            return None;
+        }
+        let size = resolution as f32;
+        Some(Vector3::new(
+            (clip.x / clip.w * 0.5 + 0.5) * size,
+            (clip.y / clip.w * 0.5 + 0.5) * size,
+            clip.z / clip.w,
+        ))
+    }
+
+    // Rasterización solo de profundidad; guarda la distancia lineal a la luz
+    fn draw_triangle(&mut self, triangle: &[Vector3; 3], resolution: i32) {
+        let (Some(a), Some(b), Some(c)) = (
+            self.project(triangle[0], resolution),
+            self.project(triangle[1], resolution),
+            self.project(triangle[2], resolution),
+        ) else {
+            return;
+        };
+
+        let area = (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x);
+        if area.abs() < 1e-10 {
+            return;
+        }
+
+        let min_x = (a.x.min(b.x).min(c.x).floor() as i32).max(0);
+        let max_x = (a.x.max(b.x).max(c.x).ceil() as i32).min(resolution - 1);
+        let min_y = (a.y.min(b.y).min(c.y).floor() as i32).max(0);
+        let max_y = (a.y.max(b.y).max(c.y).ceil() as i32).min(resolution - 1);
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let w0 = ((b.x - px) * (c.y - py) - (b.y - py) * (c.x - px)) / area;
+                let w1 = ((c.x - px) * (a.y - py) - (c.y - py) * (a.x - px)) / area;
+                let w2 = 1.0 - w0 - w1;
+                if w0 < 0.0 || w1 < 0.0 || w2 < 0.0 {
+                    continue;
+                }
+
+                let ndc_z = w0 * a.z + w1 * b.z + w2 * c.z;
+                let distance = linearize_depth(ndc_z, &self.projection);
+                let index = (y * resolution + x) as usize;
+                if distance < self.depth[index] {
+                    self.depth[index] = distance;
+                }
+            }
+        }
+    }
+
+    // Fracción iluminada del punto con PCF: compara contra (2r+1)² texels vecinos
+    fn visibility(&self, point: Vector3, settings: &ShadowSettings) -> f32 {
+        let resolution = settings.resolution;
+        let Some(projected) = self.project(point, resolution) else {
+            return 1.0;
+        };
+        let distance = linearize_depth(projected.z, &self.projection);
+        let (cx, cy) = (projected.x.floor() as i32, projected.y.floor() as i32);
+
+        let radius = settings.pcf_radius.max(0);
+        let mut lit = 0.0;
+        let mut samples = 0.0;
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                let (x, y) = (cx + dx, cy + dy);
+                samples += 1.0;
+                // fuera del mapa no hay información: se toma como iluminado
+                if x < 0 || x >= resolution || y < 0 || y >= resolution {
+                    lit += 1.0;
+                    continue;
+                }
+                if distance - settings.bias <= self.depth[(y * resolution + x) as usize] {
+                    lit += 1.0;
+                }
+            }
+        }
+        lit / samples
+    }
+}
+
+impl ShadowMap {
+    /// Pase de profundidad de toda la escena desde `light`. None si no hay nada que
+    /// proyecte sombra: así los shaders no filtran contra un mapa vacío. `uniforms` es lo
+    /// que reciben los vertex shaders de los objetos.
+    pub fn render(light: &Light, casters: &[ShadowCaster], uniforms: &Uniforms, settings: ShadowSettings) -> Option<Self> {
+        if casters.is_empty() {
+            return None;
+        }
+
+        let mut world_triangles: Vec<[Vector3; 3]> = Vec::new();
+        for caster in casters {
+            let caster_uniforms = uniforms.with_model_matrix(caster.model_matrix);
+            let to_world = |v: &Vertex| {
+                let (position, _) = (caster.vertex_shader)(v, &caster_uniforms);
+                let p = multiply_matrix_vector4(&caster.model_matrix, &Vector4::new(position.x, position.y, position.z, 1.0));
+                Vector3::new(p.x, p.y, p.z)
+            };
+            for tri in caster.vertices.chunks_exact(3) {
+                world_triangles.push([to_world(&tri[0]), to_world(&tri[1]), to_world(&tri[2])]);
+            }
+        }
+
+        let resolution = settings.resolution.max(1);
+        let near = 0.05;
+        let far = light.range.max(near * 2.0);
+
+        let mut faces = match light.kind {
+            LightKind::Point => {
+                // +X, -X, +Y, -Y, +Z, -Z con un fov de 90° cada una
+                let axes = [
+                    Vector3::new(1.0, 0.0, 0.0), Vector3::new(-1.0, 0.0, 0.0),
+                    Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, -1.0, 0.0),
+                    Vector3::new(0.0, 0.0, 1.0), Vector3::new(0.0, 0.0, -1.0),
+                ];
+                let projection = create_projection_matrix(std::f32::consts::FRAC_PI_2, 1.0, near, far);
+                axes.iter()
+                    .map(|&axis| {
+                        let view = create_view_matrix(light.position, light.position + axis, up_for(axis));
+                        ShadowFace::new(view, projection, resolution)
+                    })
+                    .collect::<Vec<_>>()
+            }
+            LightKind::Spot { outer_angle, .. } => {
+                let projection = create_projection_matrix((outer_angle * 2.0).min(3.0), 1.0, near, far);
+                let view = create_view_matrix(light.position, light.position + light.direction, up_for(light.direction));
+                vec![ShadowFace::new(view, projection, resolution)]
+            }
+            LightKind::Directional => {
+                let (center, radius) = scene_bounds(&world_triangles);
+                let eye = center - light.direction * (radius * 2.0);
+                let view = create_view_matrix(eye, center, up_for(light.direction));
+                let projection = create_orthographic_matrix(-radius, radius, -radius, radius, near, radius * 4.0);
+                vec![ShadowFace::new(view, projection, resolution)]
+            }
+        };
+
+        for face in &mut faces {
+            for triangle in &world_triangles {
+                face.draw_triangle(triangle, resolution);
+            }
+        }
+
+        Some(ShadowMap {
+            faces,
+            settings: ShadowSettings { resolution, ..settings },
+            kind: light.kind,
+            position: light.position,
+        })
+    }
+
+    /// Fracción de la luz que llega a `point` (1 = sin sombra), filtrada con PCF.
+    /// `normal` se usa para el normal bias.
+    pub fn visibility(&self, point: Vector3, normal: Vector3) -> f32 {
+        let point = point + normal * self.settings.normal_bias;
+
+        let face = match self.kind {
+            LightKind::Point => {
+                // la cara del cubo es la del eje dominante de la dirección desde la luz
+                let d = point - self.position;
+                let (ax, ay, az) = (d.x.abs(), d.y.abs(), d.z.abs());
+                if ax >= ay && ax >= az {
+                    if d.x > 0.0 { 0 } else { 1 }
+                } else if ay >= az {
+                    if d.y > 0.0 { 2 } else { 3 }
+                } else if d.z > 0.0 {
+                    4
+                } else {
+                    5
+                }
+            }
+            _ => 0,
+        };
+
+        self.faces[face].visibility(point, &self.settings)
+    }
+}
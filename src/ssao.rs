@@ -0,0 +1,188 @@
+// ssao.rs
+#![allow(dead_code)]
+
+use raylib::prelude::*;
+use crate::Uniforms;
+use crate::framebuffer::Framebuffer;
+use crate::matrix::{linearize_depth, multiply_matrix_vector4};
+use crate::shaders::{hash, smoothstep};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SsaoQuality {
+    Off,
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SsaoSettings {
+    pub samples: usize,     // muestras del hemisferio por píxel
+    pub radius: f32,        // radio del hemisferio en unidades de mundo
+    pub bias: f32,          // margen de profundidad para no ocluirse a sí mismo
+    pub blur_radius: i32,   // radio del blur en píxeles, para quitar el ruido del patrón
+    pub strength: f32,      // exponente aplicado al resultado (>1 oscurece más)
+}
+
+impl SsaoQuality {
+    pub fn settings(self) -> Option<SsaoSettings> {
+        match self {
+            SsaoQuality::Off => None,
+            SsaoQuality::Low => Some(SsaoSettings { samples: 8, radius: 0.3, bias: 0.02, blur_radius: 1, strength: 1.0 }),
+            SsaoQuality::Medium => Some(SsaoSettings { samples: 16, radius: 0.4, bias: 0.02, blur_radius: 2, strength: 1.2 }),
+            SsaoQuality::High => Some(SsaoSettings { samples: 32, radius: 0.5, bias: 0.015, blur_radius: 2, strength: 1.4 }),
+        }
+    }
+
+    /// Siguiente preset, para alternarlos con una tecla.
+    pub fn next(self) -> Self {
+        match self {
+            SsaoQuality::Off => SsaoQuality::Low,
+            SsaoQuality::Low => SsaoQuality::Medium,
+            SsaoQuality::Medium => SsaoQuality::High,
+            SsaoQuality::High => SsaoQuality::Off,
+        }
+    }
+}
+
+// Muestras dentro del hemisferio +z, más densas cerca del centro
+fn hemisphere_kernel(samples: usize) -> Vec<Vector3> {
+    (0..samples)
+        .map(|i| {
+            let seed = i as f32 * 3.17;
+            let direction = Vector3::new(hash(seed) * 2.0 - 1.0, hash(seed + 1.0) * 2.0 - 1.0, hash(seed + 2.0).max(0.05)).normalized();
+            let t = i as f32 / samples as f32;
+            direction * (0.1 + 0.9 * t * t) * hash(seed + 3.0).max(0.2)
+        })
+        .collect()
+}
+
+/// Oclusión ambiental por píxel en [0, 1] (1 = nada la tapa).
+pub struct AmbientOcclusion {
+    pub width: i32,
+    pub height: i32,
+    values: Vec<f32>,
+}
+
+impl AmbientOcclusion {
+    pub fn at(&self, x: i32, y: i32) -> f32 {
+        if x >= 0 && x < self.width && y >= 0 && y < self.height {
+            self.values[(y * self.width + x) as usize]
+        } else {
+            1.0
+        }
+    }
+
+    /// Calcula la oclusión a partir de la profundidad y las normales que quedaron en el
+    /// framebuffer: para cada píxel se reconstruye su posición en espacio vista y se cuenta
+    /// cuántas muestras del hemisferio alrededor de la normal quedan detrás de la geometría.
+    pub fn compute(framebuffer: &Framebuffer, uniforms: &Uniforms, settings: &SsaoSettings) -> Self {
+        let (width, height) = (framebuffer.width, framebuffer.height);
+        // solo la vista que se está dibujando; el resto queda sin oclusión
+        let area = framebuffer.scissor();
+        let projection = &uniforms.projection_matrix;
+        let viewport = &uniforms.viewport_matrix;
+        let kernel = hemisphere_kernel(settings.samples.max(1));
+
+        // píxel + profundidad NDC -> posición en espacio vista (sirve para perspectiva y ortográfica)
+        let view_position = |x: i32, y: i32, ndc_z: f32| {
+            let ndc_x = (x as f32 + 0.5 - viewport.m12) / viewport.m0;
+            let ndc_y = (y as f32 + 0.5 - viewport.m13) / viewport.m5;
+            let z = -linearize_depth(ndc_z, projection);
+            let w = projection.m11 * z + projection.m15;
+            Vector3::new(
+                (ndc_x * w - projection.m8 * z - projection.m12) / projection.m0,
+                (ndc_y * w - projection.m9 * z - projection.m13) / projection.m5,
+                z,
+            )
+        };
+
+        let mut raw = vec![1.0; (width * height) as usize];
+        for y in area.y..area.y + area.height {
+            for x in area.x..area.x + area.width {
+                let depth = framebuffer.get_depth(x, y);
+                if !depth.is_finite() {
+                    continue;
+                }
+                let normal_world = framebuffer.get_normal(x, y);
+                if normal_world.length() <= 0.0 {
+                    continue;
+                }
+
+                let position = view_position(x, y, depth);
+                let n = multiply_matrix_vector4(&uniforms.view_matrix, &Vector4::new(normal_world.x, normal_world.y, normal_world.z, 0.0));
+                let normal = Vector3::new(n.x, n.y, n.z).normalized();
+
+                // base tangente girada por píxel (patrón de 4x4) para cambiar bandas por ruido
+                let angle = hash((x % 4) as f32 * 7.0 + (y % 4) as f32 * 31.0) * std::f32::consts::TAU;
+                let random = Vector3::new(angle.cos(), angle.sin(), 0.0);
+                let tangent = (random - normal * random.dot(normal)).normalized();
+                let tangent = if tangent.length() > 0.0 { tangent } else { normal.perpendicular().normalized() };
+                let bitangent = normal.cross(tangent);
+
+                let mut occlusion = 0.0;
+                for sample in &kernel {
+                    let offset = tangent * sample.x + bitangent * sample.y + normal * sample.z;
+                    let sample_position = position + offset * settings.radius;
+
+                    let clip = multiply_matrix_vector4(projection, &Vector4::new(sample_position.x, sample_position.y, sample_position.z, 1.0));
+                    if clip.w <= 1e-6 {
+                        continue;
+                    }
+                    let screen = multiply_matrix_vector4(viewport, &Vector4::new(clip.x / clip.w, clip.y / clip.w, 0.0, 1.0));
+                    let scene_depth = framebuffer.get_depth(screen.x as i32, screen.y as i32);
+                    if !scene_depth.is_finite() {
+                        continue;
+                    }
+
+                    let scene_z = -linearize_depth(scene_depth, projection);
+                    // las diferencias mucho mayores que el radio son otro objeto lejano, no un pliegue
+                    let range = smoothstep(0.0, 1.0, settings.radius / (position.z - scene_z).abs().max(1e-4));
+                    if scene_z >= sample_position.z + settings.bias {
+                        occlusion += range;
+                    }
+                }
+
+                let ao = 1.0 - occlusion / kernel.len() as f32;
+                raw[(y * width + x) as usize] = ao.clamp(0.0, 1.0).powf(settings.strength);
+            }
+        }
+
+        // blur de caja solo entre píxeles con geometría, para no mezclar con el fondo
+        let radius = settings.blur_radius.max(0);
+        let mut values = raw.clone();
+        for y in area.y..area.y + area.height {
+            for x in area.x..area.x + area.width {
+                if !framebuffer.get_depth(x, y).is_finite() {
+                    continue;
+                }
+                let mut sum = 0.0;
+                let mut count = 0.0;
+                for dy in -radius..=radius {
+                    for dx in -radius..=radius {
+                        let (nx, ny) = (x + dx, y + dy);
+                        if !area.contains(nx, ny) || !framebuffer.get_depth(nx, ny).is_finite() {
+                            continue;
+                        }
+                        sum += raw[(ny * width + nx) as usize];
+                        count += 1.0;
+                    }
+                }
+                values[(y * width + x) as usize] = sum / count;
+            }
+        }
+
+        AmbientOcclusion { width, height, values }
+    }
+
+    /// Vista de depuración: reemplaza la imagen por la oclusión en escala de grises.
+    pub fn draw_debug(&self, framebuffer: &mut Framebuffer) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let value = (self.at(x, y) * 255.0) as u8;
+                framebuffer.set_current_color(Color::new(value, value, value, 255));
+                framebuffer.set_pixel(x, y);
+            }
+        }
+    }
+}
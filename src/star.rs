@@ -0,0 +1,229 @@
+// star.rs
+#![allow(dead_code)]
+
+use raylib::prelude::*;
+use noise::{NoiseFn, Perlin, Worley};
+use noise::core::worley::ReturnType;
+use std::f32::consts::PI;
+use crate::Uniforms;
+use crate::fragment::Fragment;
+use crate::framebuffer::Framebuffer;
+use crate::matrix::multiply_matrix_vector4;
+use crate::shaders::{hash, project_world_to_screen, smoothstep};
+
+// Cantidad de prominencias que pueden estar vivas a la vez
+const FLARE_SLOTS: u32 = 4;
+
+thread_local! {
+    // Los generadores construyen su tabla de permutación al crearse, así que se
+    // crean una sola vez por hilo en lugar de una vez por fragmento.
+    static GRANULATION: Worley = Worley::new(7).set_return_type(ReturnType::Distance);
+    static PERLIN: Perlin = Perlin::new(21);
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct StarParams {
+    pub temperature: f32,        // Kelvin, define el color de la estrella
+    pub radius: f32,             // radio en unidades de mundo (para la corona)
+    pub granulation_scale: f32,  // cantidad de celdas de convección
+    pub granulation_speed: f32,  // qué tan rápido evolucionan las celdas
+    pub spot_threshold: f32,     // valor de ruido a partir del cual aparecen manchas
+    pub limb_darkening: f32,     // coeficiente u de la ley lineal I(mu) = 1 - u(1 - mu)
+    pub corona_size: f32,        // alcance de la corona, en radios estelares
+    pub corona_intensity: f32,
+    pub flare_rate: f32,         // ciclos de prominencias por segundo
+    pub luminosity: f32,         // intensidad de la luz que emite, a una unidad de distancia
+}
+
+impl Default for StarParams {
+    fn default() -> Self {
+        StarParams {
+            temperature: 5778.0,
+            radius: 0.5,
+            granulation_scale: 14.0,
+            granulation_speed: 0.15,
+            spot_threshold: 0.35,
+            limb_darkening: 0.6,
+            corona_size: 1.5,
+            corona_intensity: 0.8,
+            flare_rate: 0.15,
+            luminosity: 12.0,
+        }
+    }
+}
+
+impl StarParams {
+    /// Emisión de la estrella como luz de la escena: el color de su temperatura por su luminosidad.
+    pub fn emission(&self) -> Vector3 {
+        temperature_to_color(self.temperature) * self.luminosity
+    }
+}
+
+/// Color aproximado de un cuerpo negro a la temperatura dada (Kelvin), en [0, 1].
+/// Aproximación de Tanner Helland, válida entre ~1000K y 40000K.
+pub fn temperature_to_color(kelvin: f32) -> Vector3 {
+    let t = kelvin.clamp(1000.0, 40000.0) / 100.0;
+
+    let r = if t <= 66.0 {
+        255.0
+    } else {
+        329.69873 * (t - 60.0).powf(-0.133_204_76)
+    };
+
+    let g = if t <= 66.0 {
+        99.4708 * t.ln() - 161.11957
+    } else {
+        288.12216 * (t - 60.0).powf(-0.075_514_85)
+    };
+
+    let b = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.51773 * (t - 10.0).ln() - 305.0448
+    };
+
+    Vector3::new(
+        r.clamp(0.0, 255.0) / 255.0,
+        g.clamp(0.0, 255.0) / 255.0,
+        b.clamp(0.0, 255.0) / 255.0,
+    )
+}
+
+pub fn star_fragment_shader(fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+    let star = &uniforms.star;
+    let time = uniforms.time;
+
+    // Dirección sobre la esfera en espacio objeto: el patrón gira junto con el modelo
+    let dir = fragment.vertex_position.normalized();
+
+    // Granulación: celdas de convección con centros calientes y bordes oscuros
+    let p = dir * star.granulation_scale;
+    let f1 = GRANULATION.with(|n| n.get([p.x as f64, p.y as f64, p.z as f64, (time * star.granulation_speed) as f64]));
+    let f1 = ((f1 + 1.0) * 0.5) as f32;
+    let granule = 1.0 - smoothstep(0.05, 0.8, f1);
+
+    // Manchas solares: ruido de baja frecuencia que cambia muy lento (umbra + penumbra)
+    let s = dir * 1.8;
+    let spot_noise = PERLIN.with(|n| n.get([s.x as f64, s.y as f64, s.z as f64, (time * 0.02) as f64])) as f32;
+    let penumbra = smoothstep(star.spot_threshold - 0.08, star.spot_threshold, spot_noise);
+    let umbra = smoothstep(star.spot_threshold + 0.04, star.spot_threshold + 0.12, spot_noise);
+    let spot_factor = 1.0 - 0.35 * penumbra - 0.5 * umbra;
+
+    let hot = temperature_to_color(star.temperature * 1.1);
+    let cool = temperature_to_color(star.temperature * 0.75);
+    let surface = cool.lerp(hot, granule) * spot_factor;
+
+    // Oscurecimiento al limbo: mu es el coseno entre la normal y la dirección a la cámara.
+    // En espacio de vista la cámara mira hacia -Z, así que mu es la componente z de la normal.
+    let normal_world = multiply_matrix_vector4(&uniforms.model_matrix, &Vector4::new(dir.x, dir.y, dir.z, 0.0));
+    let normal_view = multiply_matrix_vector4(&uniforms.view_matrix, &normal_world);
+    let normal_view = Vector3::new(normal_view.x, normal_view.y, normal_view.z).normalized();
+    let mu = normal_view.z.clamp(0.0, 1.0);
+    let limb = 1.0 - star.limb_darkening * (1.0 - mu);
+
+    surface * limb * 1.3
+}
+
+// Intensidad de las prominencias en un punto de la corona, dado su ángulo alrededor
+// del centro y su altura sobre el limbo (en radios estelares)
+fn prominence_intensity(angle: f32, height: f32, time: f32, star: &StarParams) -> f32 {
+    let mut total = 0.0;
+
+    for slot in 0..FLARE_SLOTS {
+        let cycle = time * star.flare_rate + slot as f32 / FLARE_SLOTS as f32;
+        let epoch = cycle.floor();
+        let phase = cycle - epoch;
+        let seed = epoch * 13.17 + slot as f32 * 7.31;
+
+        // no todos los ciclos producen una prominencia
+        if hash(seed) > 0.6 {
+            continue;
+        }
+
+        let base_angle = hash(seed + 1.0) * 2.0 * PI;
+        let span = 0.12 + hash(seed + 2.0) * 0.25;
+        let max_height = (0.15 + hash(seed + 3.0) * 0.45) * star.corona_size;
+
+        // diferencia angular en [-PI, PI]
+        let mut delta = angle - base_angle;
+        delta = (delta + PI).rem_euclid(2.0 * PI) - PI;
+        let u = delta / span;
+        if u.abs() > 1.0 {
+            continue;
+        }
+
+        // el arco crece y luego se apaga a lo largo del ciclo
+        let life = (phase * PI).sin();
+        let arc_height = max_height * (1.0 - u * u).sqrt() * life;
+        let thickness = 0.03 + 0.02 * life;
+        let d = (height - arc_height) / thickness;
+
+        total += (-d * d).exp() * life;
+    }
+
+    total
+}
+
+/// Dibuja la corona y las prominencias alrededor de una estrella centrada en `center`
+/// (espacio mundo). Solo afecta pixeles de fondo, así que se llama después del render.
+pub fn draw_corona(framebuffer: &mut Framebuffer, uniforms: &Uniforms, center: Vector3) {
+    let star = &uniforms.star;
+    let time = uniforms.time;
+
+    // Radio en pantalla: proyectamos un punto del borde usando el eje "right" de la cámara
+    let view = &uniforms.view_matrix;
+    let right = Vector3::new(view.m0, view.m4, view.m8);
+    let center_screen = project_world_to_screen(center, uniforms);
+    let edge_screen = project_world_to_screen(center + right * star.radius, uniforms);
+    let radius = (edge_screen - center_screen).length();
+
+    if !radius.is_finite() || radius < 1.0 {
+        return;
+    }
+
+    let outer = radius * (1.0 + star.corona_size);
+    let corona_color = temperature_to_color(star.temperature * 1.4);
+    let flare_color = Vector3::new(1.0, 0.45, 0.25).lerp(temperature_to_color(star.temperature), 0.3);
+
+    let area = framebuffer.scissor();
+    let min_x = ((center_screen.x - outer).floor() as i32).max(area.x);
+    let max_x = ((center_screen.x + outer).ceil() as i32).min(area.x + area.width - 1);
+    let min_y = ((center_screen.y - outer).floor() as i32).max(area.y);
+    let max_y = ((center_screen.y + outer).ceil() as i32).min(area.y + area.height - 1);
+
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            // pixeles ya ocupados por geometría (la estrella u otros objetos) no reciben corona
+            if framebuffer.get_depth(x, y).is_finite() {
+                continue;
+            }
+
+            let dx = x as f32 + 0.5 - center_screen.x;
+            let dy = y as f32 + 0.5 - center_screen.y;
+            let dist = (dx * dx + dy * dy).sqrt();
+            if dist > outer {
+                continue;
+            }
+
+            let height = (dist - radius).max(0.0) / radius;
+            let angle = dy.atan2(dx);
+
+            // serpentinas: variación angular lenta del brillo de la corona
+            let streamer = PERLIN.with(|n| n.get([
+                (angle.cos() * 2.5) as f64,
+                (angle.sin() * 2.5) as f64,
+                (height * 1.5) as f64,
+                (time * 0.1) as f64,
+            ])) as f32;
+            let streamer = 0.6 + 0.8 * (streamer + 1.0) * 0.5;
+
+            let fade = 1.0 - smoothstep(0.7, 1.0, dist / outer);
+            let corona = (-height * 4.0 / star.corona_size).exp() * streamer * star.corona_intensity * fade;
+            let flare = prominence_intensity(angle, height, time, star) * fade;
+
+            framebuffer.add_color(x, y, corona_color * corona + flare_color * flare);
+        }
+    }
+}
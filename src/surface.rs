@@ -0,0 +1,161 @@
+// surface.rs
+#![allow(dead_code)]
+
+use raylib::prelude::*;
+use std::f32::consts::PI;
+use crate::Uniforms;
+use crate::fragment::Fragment;
+use crate::ambient::ambient_light;
+use crate::matrix::multiply_matrix_vector4;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LightingModel {
+    BlinnPhong,
+    // metallic/roughness con BRDF de Cook-Torrance (GGX)
+    Pbr,
+}
+
+/// Propiedades de iluminación de la superficie, leídas del .mtl del modelo
+/// (Ka, Kd, Ks, Ns, Ke, y Pm/Pr de la extensión PBR si están).
+#[derive(Clone, Copy, Debug)]
+pub struct SurfaceMaterial {
+    pub ambient: Vector3,    // Ka
+    pub diffuse: Vector3,    // Kd
+    pub specular: Vector3,   // Ks
+    pub shininess: f32,      // Ns, exponente de Blinn-Phong
+    pub emissive: Vector3,   // Ke
+    pub metallic: f32,       // Pm
+    pub roughness: f32,      // Pr; si falta se deriva de Ns
+    pub model: LightingModel,
+}
+
+impl Default for SurfaceMaterial {
+    fn default() -> Self {
+        // el color base que usaba el rasterizador antes de mover la iluminación al fragment stage
+        SurfaceMaterial {
+            ambient: Vector3::new(1.0, 1.0, 1.0),
+            diffuse: Vector3::new(0.2, 0.9, 0.7),
+            specular: Vector3::new(0.0, 0.0, 0.0),
+            shininess: 32.0,
+            emissive: Vector3::zero(),
+            metallic: 0.0,
+            roughness: roughness_from_shininess(32.0),
+            model: LightingModel::BlinnPhong,
+        }
+    }
+}
+
+// Aproximación usual de la rugosidad equivalente a un exponente de Phong
+fn roughness_from_shininess(shininess: f32) -> f32 {
+    (2.0 / (shininess + 2.0)).sqrt().clamp(0.04, 1.0)
+}
+
+fn vector_from(values: Option<[f32; 3]>, default: Vector3) -> Vector3 {
+    values.map(|[x, y, z]| Vector3::new(x, y, z)).unwrap_or(default)
+}
+
+fn parse_vector(text: &str) -> Option<Vector3> {
+    let values: Vec<f32> = text.split_whitespace().filter_map(|t| t.parse().ok()).collect();
+    match values.as_slice() {
+        [x, y, z] => Some(Vector3::new(*x, *y, *z)),
+        [v] => Some(Vector3::new(*v, *v, *v)),
+        _ => None,
+    }
+}
+
+impl SurfaceMaterial {
+    pub fn from_mtl(material: &tobj::Material) -> Self {
+        let default = SurfaceMaterial::default();
+        let shininess = material.shininess.unwrap_or(default.shininess);
+
+        // tobj deja Ke, Pm y Pr como parámetros desconocidos
+        let param = |name: &str| material.unknown_param.get(name);
+        let emissive = param("Ke").and_then(|v| parse_vector(v)).unwrap_or(default.emissive);
+        let metallic = param("Pm").and_then(|v| v.trim().parse().ok()).unwrap_or(default.metallic);
+        let roughness = param("Pr").and_then(|v| v.trim().parse().ok()).unwrap_or_else(|| roughness_from_shininess(shininess));
+
+        SurfaceMaterial {
+            ambient: vector_from(material.ambient, default.ambient),
+            diffuse: vector_from(material.diffuse, default.diffuse),
+            specular: vector_from(material.specular, default.specular),
+            shininess,
+            emissive,
+            metallic,
+            roughness,
+            model: default.model,
+        }
+    }
+
+    /// Ilumina la superficie con todas las luces de la escena. `albedo` reemplaza a Kd,
+    /// para que los shaders con patrones puedan usar el mismo modelo de iluminación.
+    /// `occlusion` (del SSAO) solo afecta a la luz ambiente.
+    pub fn shade(&self, albedo: Vector3, position: Vector3, normal: Vector3, occlusion: f32, uniforms: &Uniforms) -> Vector3 {
+        let view_dir = (uniforms.eye - position).normalized();
+        let mut color = self.emissive + self.ambient * albedo * ambient_light(normal, uniforms) * occlusion;
+
+        for (light_dir, radiance) in uniforms.incident_lights(position, normal) {
+            let n_dot_l = normal.dot(light_dir);
+            if n_dot_l <= 0.0 {
+                continue;
+            }
+
+            let reflected = match self.model {
+                LightingModel::BlinnPhong => self.blinn_phong(albedo, normal, light_dir, view_dir),
+                LightingModel::Pbr => self.cook_torrance(albedo, normal, light_dir, view_dir),
+            };
+            color += reflected * radiance * n_dot_l;
+        }
+
+        color
+    }
+
+    fn blinn_phong(&self, albedo: Vector3, normal: Vector3, light_dir: Vector3, view_dir: Vector3) -> Vector3 {
+        let half = (light_dir + view_dir).normalized();
+        let specular = normal.dot(half).max(0.0).powf(self.shininess.max(1.0));
+        albedo + self.specular * specular
+    }
+
+    // BRDF multiplicada por PI: así una superficie blanca mate recibe lo mismo que con Lambert
+    fn cook_torrance(&self, albedo: Vector3, normal: Vector3, light_dir: Vector3, view_dir: Vector3) -> Vector3 {
+        let half = (light_dir + view_dir).normalized();
+        let n_dot_l = normal.dot(light_dir).max(1e-4);
+        let n_dot_v = normal.dot(view_dir).max(1e-4);
+        let n_dot_h = normal.dot(half).max(0.0);
+        let v_dot_h = view_dir.dot(half).max(0.0);
+
+        let alpha = self.roughness * self.roughness;
+        let alpha2 = alpha * alpha;
+
+        // distribución GGX
+        let denom = n_dot_h * n_dot_h * (alpha2 - 1.0) + 1.0;
+        let distribution = alpha2 / (PI * denom * denom);
+
+        // geometría de Smith con Schlick-GGX
+        let k = (self.roughness + 1.0) * (self.roughness + 1.0) / 8.0;
+        let geometry = (n_dot_l / (n_dot_l * (1.0 - k) + k)) * (n_dot_v / (n_dot_v * (1.0 - k) + k));
+
+        // Fresnel de Schlick; los metales reflejan con su propio color
+        let f0 = Vector3::new(0.04, 0.04, 0.04).lerp(albedo, self.metallic);
+        let fresnel = f0 + (Vector3::one() - f0) * (1.0 - v_dot_h).powi(5);
+
+        let specular = fresnel * (distribution * geometry / (4.0 * n_dot_l * n_dot_v));
+        let diffuse = (Vector3::one() - fresnel) * albedo * (1.0 - self.metallic);
+
+        diffuse + specular * PI
+    }
+}
+
+/// Posición en espacio mundo del fragmento (el rasterizador interpola la de espacio objeto).
+pub fn fragment_world_position(fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+    let p = fragment.vertex_position;
+    let world = multiply_matrix_vector4(&uniforms.model_matrix, &Vector4::new(p.x, p.y, p.z, 1.0));
+    Vector3::new(world.x, world.y, world.z)
+}
+
+/// Shader que solo aplica el material del modelo (Blinn-Phong o PBR según `model`).
+pub fn lit_fragment_shader(fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+    let surface = &uniforms.surface;
+    let position = fragment_world_position(fragment, uniforms);
+    let occlusion = uniforms.ambient_occlusion_at(fragment.position);
+    surface.shade(surface.diffuse, position, fragment.normal.normalized(), occlusion, uniforms)
+}
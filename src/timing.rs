@@ -0,0 +1,82 @@
+// timing.rs
+#![allow(dead_code)]
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Bucle de juego con paso fijo: el tiempo real de cada cuadro se acumula y se consume en
+/// pasos de `step` segundos, así la simulación no depende de cuánto tarda en dibujarse un
+/// cuadro. Lo que sobra del acumulador es `alpha`, para interpolar entre los dos últimos pasos.
+pub struct FixedTimestep {
+    pub step: f32,             // segundos por paso de simulación
+    pub max_frame_time: f32,   // tope del tiempo de un cuadro, para no encadenar pasos sin fin
+    pub frame_cap: Option<f32>, // cuadros por segundo máximos (None = sin límite)
+    // si está, cada cuadro cuenta exactamente este tiempo sin mirar el reloj: para exportar
+    // cuadros de video, donde lo que se dibuja no debe depender de cuánto tarda
+    pub fixed_frame_time: Option<f32>,
+    accumulator: f32,
+    last_frame: Instant,
+    frame_start: Instant,
+}
+
+impl FixedTimestep {
+    pub fn new(step: f32) -> Self {
+        let now = Instant::now();
+        FixedTimestep {
+            step,
+            max_frame_time: 0.25,
+            frame_cap: Some(60.0),
+            fixed_frame_time: None,
+            accumulator: 0.0,
+            last_frame: now,
+            frame_start: now,
+        }
+    }
+
+    /// Empieza un cuadro: mide el tiempo desde el anterior, lo suma al acumulador y lo devuelve.
+    pub fn begin_frame(&mut self) -> f32 {
+        let now = Instant::now();
+        let dt = match self.fixed_frame_time {
+            Some(dt) => dt,
+            None => (now - self.last_frame).as_secs_f32().min(self.max_frame_time),
+        };
+        self.last_frame = now;
+        self.frame_start = now;
+        self.accumulator += dt;
+        dt
+    }
+
+    /// Vacía el acumulador y vuelve a medir desde ahora.
+    pub fn reset(&mut self) {
+        let now = Instant::now();
+        self.accumulator = 0.0;
+        self.last_frame = now;
+        self.frame_start = now;
+    }
+
+    /// true mientras quede al menos un paso entero en el acumulador (y lo consume).
+    pub fn step(&mut self) -> bool {
+        if self.accumulator >= self.step {
+            self.accumulator -= self.step;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Fracción del siguiente paso que ya pasó, en [0, 1).
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / self.step
+    }
+
+    /// Termina el cuadro: si hay límite de cuadros por segundo, duerme lo que falte.
+    pub fn end_frame(&self) {
+        if let (Some(fps), None) = (self.frame_cap, self.fixed_frame_time) {
+            let target = Duration::from_secs_f32(1.0 / fps.max(1.0));
+            let spent = self.frame_start.elapsed();
+            if spent < target {
+                thread::sleep(target - spent);
+            }
+        }
+    }
+}
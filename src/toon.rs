@@ -0,0 +1,125 @@
+// toon.rs
+#![allow(dead_code)]
+
+use raylib::prelude::*;
+use crate::Uniforms;
+use crate::fragment::Fragment;
+use crate::framebuffer::Framebuffer;
+use crate::matrix::{linearize_depth, multiply_matrix_vector4};
+use crate::shaders::smoothstep;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ToonParams {
+    pub bands: f32,              // cantidad de escalones de N·L
+    pub base_color: Vector3,
+    pub ambient: f32,            // luz mínima del lado oscuro
+    pub rim_strength: f32,
+    pub rim_power: f32,          // qué tan fino es el brillo del borde
+    pub rim_color: Vector3,
+    pub outline_thickness: i32,  // en píxeles
+    pub outline_color: Color,
+    pub depth_threshold: f32,    // salto relativo de profundidad lineal que cuenta como borde
+    pub normal_threshold: f32,   // coseno mínimo entre normales vecinas para no ser borde
+}
+
+impl Default for ToonParams {
+    fn default() -> Self {
+        ToonParams {
+            bands: 4.0,
+            base_color: Vector3::new(0.35, 0.65, 0.95),
+            ambient: 0.15,
+            rim_strength: 0.6,
+            rim_power: 3.0,
+            rim_color: Vector3::new(1.0, 1.0, 1.0),
+            outline_thickness: 2,
+            outline_color: Color::new(10, 8, 20, 255),
+            depth_threshold: 0.08,
+            normal_threshold: 0.6,
+        }
+    }
+}
+
+/// Sombreado cel: N·L cuantizado en escalones planos más un brillo de borde (rim) que
+/// depende del ángulo entre la normal y la dirección hacia la cámara.
+pub fn toon_fragment_shader(fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+    let toon = &uniforms.toon;
+    let p = fragment.vertex_position;
+    let world = multiply_matrix_vector4(&uniforms.model_matrix, &Vector4::new(p.x, p.y, p.z, 1.0));
+    let world_pos = Vector3::new(world.x, world.y, world.z);
+
+    let normal = fragment.normal.normalized();
+
+    // cada luz aporta su propio escalón, teñido con su color
+    let bands = toon.bands.max(1.0);
+    let ambient = toon.ambient * uniforms.ambient_occlusion_at(fragment.position);
+    let mut diffuse = Vector3::new(ambient, ambient, ambient);
+    let mut lit = 0.0;
+    for (light_dir, radiance) in uniforms.incident_lights(world_pos, normal) {
+        let n_dot_l = normal.dot(light_dir).max(0.0);
+        let stepped = (n_dot_l * bands).ceil() / bands;
+        diffuse += radiance * ((1.0 - toon.ambient) * stepped);
+        if n_dot_l > 0.0 && radiance.length() > 0.0 {
+            lit = 1.0;
+        }
+    }
+
+    // el rim se calcula en espacio vista, donde la cámara está en el origen
+    let view_pos = multiply_matrix_vector4(&uniforms.view_matrix, &world);
+    let view_normal = multiply_matrix_vector4(&uniforms.view_matrix, &Vector4::new(normal.x, normal.y, normal.z, 0.0));
+    let to_eye = Vector3::new(-view_pos.x, -view_pos.y, -view_pos.z).normalized();
+    let facing = Vector3::new(view_normal.x, view_normal.y, view_normal.z).normalized().dot(to_eye).max(0.0);
+    // también con borde duro, para que no rompa el look plano; solo del lado iluminado
+    let rim = smoothstep(0.45, 0.5, (1.0 - facing).powf(toon.rim_power)) * toon.rim_strength * lit;
+
+    toon.base_color * diffuse + toon.rim_color * rim
+}
+
+/// Post pass de contornos: marca los píxeles con geometría donde algún vecino (a
+/// `outline_thickness` píxeles) es fondo, está bastante más lejos o tiene una normal
+/// muy distinta. Necesita que el render haya guardado las normales en el framebuffer.
+pub fn draw_outlines(framebuffer: &mut Framebuffer, uniforms: &Uniforms) {
+    let toon = &uniforms.toon;
+    let offset = toon.outline_thickness.max(1);
+    let neighbors = [(offset, 0), (-offset, 0), (0, offset), (0, -offset)];
+
+    let mut edges = Vec::new();
+    let area = framebuffer.scissor();
+    for y in area.y..area.y + area.height {
+        for x in area.x..area.x + area.width {
+            let depth = framebuffer.get_depth(x, y);
+            if !depth.is_finite() {
+                continue;
+            }
+            let distance = linearize_depth(depth, &uniforms.projection_matrix);
+            let normal = framebuffer.get_normal(x, y);
+
+            let is_edge = neighbors.iter().any(|(dx, dy)| {
+                let (nx, ny) = (x + dx, y + dy);
+                if !framebuffer.in_scissor(nx, ny) {
+                    return false;
+                }
+                let neighbor_depth = framebuffer.get_depth(nx, ny);
+                // silueta contra el fondo
+                if !neighbor_depth.is_finite() {
+                    return true;
+                }
+                // solo el lado cercano dibuja el borde, para que no salga doble
+                let neighbor_distance = linearize_depth(neighbor_depth, &uniforms.projection_matrix);
+                if (neighbor_distance - distance) / distance > toon.depth_threshold {
+                    return true;
+                }
+                normal.dot(framebuffer.get_normal(nx, ny)) < toon.normal_threshold
+            });
+
+            if is_edge {
+                edges.push((x, y));
+            }
+        }
+    }
+
+    // escribimos al final para que los bordes ya pintados no afecten la detección
+    framebuffer.set_current_color(toon.outline_color);
+    for (x, y) in edges {
+        framebuffer.set_pixel(x, y);
+    }
+}
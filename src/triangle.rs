@@ -1,5 +1,4 @@
 use crate::fragment::{Fragment};
-use crate::light::Light;
 use crate::vertex::Vertex;
 use raylib::prelude::*;
 
@@ -24,9 +23,8 @@ fn barycentric_coordinates(p_x: f32, p_y: f32, a: &Vertex, b: &Vertex, c: &Verte
     (w, v, u)
 }
 
-pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex,light:&Light) -> Vec<Fragment> {
+pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex) -> Vec<Fragment> {
     let mut fragments = Vec::new();
-    let base_color = Vector3::new(0.2,0.9,0.7);
 
     let min_x = v1.transformed_position.x.min(v2.transformed_position.x).min(v3.transformed_position.x).floor() as i32;
 let max_x = v1.transformed_position.x.max(v2.transformed_position.x).max(v3.transformed_position.x).ceil() as i32;
@@ -69,34 +67,25 @@ for y in min_y..=max_y {
             w1 * v1.position.y + w2 * v2.position.y + w3 * v3.position.y ,
             w1 * v1.position.z + w2 * v2.position.z + w3 * v3.position.z ,
         ); 
-        // Dirección de la luz (desde la superficie hacia la luz) para este fragmento
-        let mut light_dir = Vector3::new(
-             light.position.x - world_pos.x,
-             light.position.y - world_pos.y,
-             light.position.z - world_pos.z,
-        );
-// Normalizar la dirección de la luz
-        let light_length = (light_dir.x * light_dir.x + light_dir.y * light_dir.y + light_dir.z * light_dir.z).sqrt();
-        if light_length >0.0{
-        light_dir.x /= light_length;
-        light_dir.y /= light_length;
-        light_dir.z /= light_length;
-        }
-
-// Calcular la intensidad de la iluminación por fragmento usando la normal interpolada (sombrado lambertiano)
-let intensity = (normalized_normal.x * light_dir.x + normalized_normal.y * light_dir.y + normalized_normal.z * light_dir.z).max(0.0);
-
-// Aplicar sombreado a color base
-let shaded_color = Vector3::new(
-base_color.x * intensity,
- base_color.y * intensity,
- base_color.z * intensity,
-);
+// La iluminación se hace en el fragment stage (ver surface.rs); aquí solo interpolamos el color de vértice
+let color = v1.color * w1 + v2.color * w2 + v3.color * w3;
 // Interpolar la profundidad usando las coordenadas baricéntricas
 let depth = w1 * v1.transformed_position.z + w2 * v2.transformed_position.z + w3 * v3.transformed_position.z;
 
 // Agregar el fragmento al buffer de fragmentos
-fragments.push(Fragment::new(p_x, p_y, shaded_color, depth));
+// Coordenadas de textura y marco tangente para normal mapping
+let tex_coords = Vector2::new(
+    w1 * v1.tex_coords.x + w2 * v2.tex_coords.x + w3 * v3.tex_coords.x,
+    w1 * v1.tex_coords.y + w2 * v2.tex_coords.y + w3 * v3.tex_coords.y,
+);
+let tangent = v1.transformed_tangent * w1 + v2.transformed_tangent * w2 + v3.transformed_tangent * w3;
+let bitangent = v1.transformed_bitangent * w1 + v2.transformed_bitangent * w2 + v3.transformed_bitangent * w3;
+
+let mut fragment = Fragment::new_with_surface(p_x, p_y, color, depth, normalized_normal, world_pos);
+fragment.tex_coords = tex_coords;
+fragment.tangent = tangent;
+fragment.bitangent = bitangent;
+fragments.push(fragment);
     }
 }
 }
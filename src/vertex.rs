@@ -8,8 +8,12 @@ pub struct Vertex{
     pub normal: Vector3,
     pub tex_coords: Vector2,
     pub color: Vector3,
+    pub tangent: Vector3,
+    pub bitangent: Vector3,
     pub transformed_position: Vector3,
     pub transformed_normal: Vector3,
+    pub transformed_tangent: Vector3,
+    pub transformed_bitangent: Vector3,
 }
 
 impl Vertex {
@@ -19,8 +23,12 @@ impl Vertex {
             normal,
             tex_coords,
             color: Vector3::new(0.0, 0.0, 0.0),
+            tangent: Vector3::new(0.0, 0.0, 0.0),
+            bitangent: Vector3::new(0.0, 0.0, 0.0),
             transformed_position: position,
             transformed_normal: normal,
+            transformed_tangent: Vector3::new(0.0, 0.0, 0.0),
+            transformed_bitangent: Vector3::new(0.0, 0.0, 0.0),
         }
 
     }
@@ -32,8 +40,12 @@ impl Vertex {
             normal: Vector3::new(0.0, 0.0, 0.0),
             tex_coords: Vector2::new(0.0, 0.0),
             color,
+            tangent: Vector3::new(0.0, 0.0, 0.0),
+            bitangent: Vector3::new(0.0, 0.0, 0.0),
             transformed_position: Vector3::new(0.0, 0.0, 0.0),
             transformed_normal: Vector3::new(0.0, 0.0, 0.0),
+            transformed_tangent: Vector3::new(0.0, 0.0, 0.0),
+            transformed_bitangent: Vector3::new(0.0, 0.0, 0.0),
         }
     }
 
@@ -51,8 +63,12 @@ impl Default for Vertex {
             normal: Vector3::new(0.0, 0.0, 0.0),
             tex_coords: Vector2::new(0.0, 0.0),
             color: Vector3::new(0.0, 0.0, 0.0),
+            tangent: Vector3::new(0.0, 0.0, 0.0),
+            bitangent: Vector3::new(0.0, 0.0, 0.0),
             transformed_position: Vector3::new(0.0, 0.0, 0.0),
             transformed_normal: Vector3::new(0.0, 0.0, 0.0),
+            transformed_tangent: Vector3::new(0.0, 0.0, 0.0),
+            transformed_bitangent: Vector3::new(0.0, 0.0, 0.0),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,88 @@
+// viewport.rs
+#![allow(dead_code)]
+
+use raylib::prelude::*;
+use crate::matrix::create_viewport_matrix;
+
+/// Rectángulo de la ventana en píxeles donde se dibuja una vista. También es el rectángulo
+/// de recorte (scissor) del framebuffer mientras se dibuja esa vista.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Viewport {
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Viewport { x, y, width: width.max(0), height: height.max(0) }
+    }
+
+    pub fn aspect(&self) -> f32 {
+        self.width as f32 / self.height.max(1) as f32
+    }
+
+    /// NDC -> píxeles dentro de este rectángulo.
+    pub fn matrix(&self) -> Matrix {
+        create_viewport_matrix(self.x as f32, self.y as f32, self.width as f32, self.height as f32)
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+
+    pub fn contains_point(&self, point: Vector2) -> bool {
+        self.contains(point.x.floor() as i32, point.y.floor() as i32)
+    }
+
+    /// La parte de este rectángulo que cae dentro de `other` (vacía si no se tocan).
+    pub fn intersection(&self, other: &Viewport) -> Viewport {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+        Viewport::new(x, y, right - x, bottom - y)
+    }
+}
+
+/// Cómo se reparte la ventana entre las vistas.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ViewportLayout {
+    // una sola vista en toda la ventana
+    Single,
+    // la vista principal y, encima, un recuadro con el mapa del sistema visto desde arriba
+    PictureInPicture,
+    // dos mitades con la misma cámara, cada una con su shader, para compararlos
+    SideBySide,
+}
+
+impl ViewportLayout {
+    pub fn next(self) -> Self {
+        match self {
+            ViewportLayout::Single => ViewportLayout::PictureInPicture,
+            ViewportLayout::PictureInPicture => ViewportLayout::SideBySide,
+            ViewportLayout::SideBySide => ViewportLayout::Single,
+        }
+    }
+
+    /// Rectángulos de las vistas en orden de dibujo: la principal primero, y lo que va
+    /// encima después.
+    pub fn viewports(&self, width: i32, height: i32) -> Vec<Viewport> {
+        match self {
+            ViewportLayout::Single => vec![Viewport::new(0, 0, width, height)],
+            ViewportLayout::PictureInPicture => {
+                let (inset_width, inset_height) = (width / 3, height / 3);
+                let margin = 12;
+                vec![
+                    Viewport::new(0, 0, width, height),
+                    Viewport::new(width - inset_width - margin, height - inset_height - margin, inset_width, inset_height),
+                ]
+            }
+            ViewportLayout::SideBySide => {
+                let half = width / 2;
+                vec![Viewport::new(0, 0, half, height), Viewport::new(half, 0, width - half, height)]
+            }
+        }
+    }
+}